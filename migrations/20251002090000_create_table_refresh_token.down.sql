@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS session_id_refresh_token_idx;
+DROP INDEX IF EXISTS account_id_refresh_token_idx;
+DROP INDEX IF EXISTS family_id_refresh_token_idx;
+DROP TABLE IF EXISTS refresh_token;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE refresh_token (
+    id VARCHAR(64) PRIMARY KEY,
+    family_id VARCHAR(64) NOT NULL,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    session_id VARCHAR(64) NOT NULL,
+    expiry_time TIMESTAMPTZ NOT NULL,
+    rotated_time TIMESTAMPTZ,
+    revoked_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX family_id_refresh_token_idx ON refresh_token(family_id);
+CREATE INDEX account_id_refresh_token_idx ON refresh_token(account_id);
+CREATE INDEX session_id_refresh_token_idx ON refresh_token(session_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS account_id_password_reset_token_idx;
+DROP TABLE IF EXISTS password_reset_token;
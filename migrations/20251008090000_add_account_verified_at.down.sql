@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS account_id_utc_create_email_verification_token_idx;
+DROP TABLE IF EXISTS email_verification_token;
+ALTER TABLE account DROP COLUMN IF EXISTS verified_at;
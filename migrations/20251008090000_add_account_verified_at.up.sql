@@ -0,0 +1,16 @@
+-- Add up migration script here
+ALTER TABLE account ADD COLUMN verified_at TIMESTAMPTZ;
+
+-- Accounts created before verification existed are treated as verified
+UPDATE account SET verified_at = utc_create;
+
+CREATE TABLE email_verification_token (
+    id VARCHAR(64) PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    expiry_time TIMESTAMPTZ NOT NULL,
+    used_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX account_id_utc_create_email_verification_token_idx ON email_verification_token(account_id, utc_create DESC);
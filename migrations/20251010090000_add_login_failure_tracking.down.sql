@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS login_failure_ip;
+ALTER TABLE account DROP COLUMN IF EXISTS locked_until;
+ALTER TABLE account DROP COLUMN IF EXISTS lockout_count;
+ALTER TABLE account DROP COLUMN IF EXISTS failed_login_count;
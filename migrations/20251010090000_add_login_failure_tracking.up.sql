@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE account ADD COLUMN failed_login_count INT4 NOT NULL DEFAULT 0;
+ALTER TABLE account ADD COLUMN lockout_count INT4 NOT NULL DEFAULT 0;
+ALTER TABLE account ADD COLUMN locked_until TIMESTAMPTZ;
+
+CREATE TABLE login_failure_ip (
+    ip VARCHAR(64) PRIMARY KEY,
+    failed_count INT4 NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS login_challenge;
+DROP INDEX IF EXISTS account_id_totp_recovery_code_idx;
+DROP TABLE IF EXISTS totp_recovery_code;
+DROP TABLE IF EXISTS account_totp;
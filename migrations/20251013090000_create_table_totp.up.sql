@@ -0,0 +1,29 @@
+-- Add up migration script here
+CREATE TABLE account_totp (
+    account_id INT4 PRIMARY KEY REFERENCES account(id) ON DELETE CASCADE,
+    secret_encrypted TEXT NOT NULL,
+    enabled_at TIMESTAMPTZ,
+    last_used_step INT8,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE totp_recovery_code (
+    id SERIAL PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    code_hash VARCHAR(64) NOT NULL,
+    used_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX account_id_totp_recovery_code_idx ON totp_recovery_code(account_id);
+
+CREATE TABLE login_challenge (
+    id VARCHAR(64) PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    expiry_time TIMESTAMPTZ NOT NULL,
+    used_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
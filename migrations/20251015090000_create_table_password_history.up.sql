@@ -0,0 +1,9 @@
+-- Add up migration script here
+CREATE TABLE password_history (
+    id SERIAL PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    password VARCHAR(255) NOT NULL,
+    utc_create TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX account_id_utc_create_password_history_idx ON password_history(account_id, utc_create);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS delete_after_account_idx;
+ALTER TABLE account DROP COLUMN IF EXISTS delete_after;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE account ADD COLUMN delete_after TIMESTAMPTZ;
+
+CREATE INDEX delete_after_account_idx ON account(delete_after) WHERE delete_after IS NOT NULL;
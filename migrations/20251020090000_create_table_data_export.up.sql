@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE data_export (
+    id VARCHAR(64) PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    status VARCHAR(16) NOT NULL,
+    ready_time TIMESTAMPTZ,
+    expiry_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX account_id_utc_create_data_export_idx ON data_export(account_id, utc_create DESC);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE account DROP COLUMN IF EXISTS role;
+DROP TYPE IF EXISTS account_role;
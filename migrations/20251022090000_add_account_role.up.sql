@@ -0,0 +1,4 @@
+-- Add up migration script here
+CREATE TYPE account_role AS ENUM ('user', 'admin');
+
+ALTER TABLE account ADD COLUMN role account_role NOT NULL DEFAULT 'user';
@@ -0,0 +1,4 @@
+-- Add up migration script here
+ALTER TABLE account ADD COLUMN disabled_at TIMESTAMPTZ;
+
+CREATE INDEX utc_create_account_idx ON account(utc_create);
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE api_key (
+    id SERIAL PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL,
+    expiry_time TIMESTAMPTZ,
+    last_used_time TIMESTAMPTZ,
+    revoked_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX account_id_api_key_idx ON api_key(account_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS account_identity;
+DROP TABLE IF EXISTS oidc_login_state;
@@ -0,0 +1,23 @@
+-- Add up migration script here
+CREATE TABLE oidc_login_state (
+    id VARCHAR(64) PRIMARY KEY,
+    provider VARCHAR(64) NOT NULL,
+    code_verifier VARCHAR(128) NOT NULL,
+    nonce VARCHAR(64) NOT NULL,
+    expiry_time TIMESTAMPTZ NOT NULL,
+    utc_create TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE account_identity (
+    id SERIAL PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    provider VARCHAR(64) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    last_login_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL,
+    UNIQUE (provider, subject)
+);
+
+CREATE INDEX account_id_account_identity_idx ON account_identity(account_id);
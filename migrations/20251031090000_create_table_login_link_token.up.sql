@@ -0,0 +1,11 @@
+-- Add up migration script here
+CREATE TABLE login_link_token (
+    id VARCHAR(64) PRIMARY KEY,
+    account_id INT4 NOT NULL REFERENCES account(id) ON DELETE CASCADE,
+    expiry_time TIMESTAMPTZ NOT NULL,
+    used_time TIMESTAMPTZ,
+    utc_create TIMESTAMPTZ NOT NULL,
+    utc_modified TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX account_id_login_link_token_idx ON login_link_token(account_id);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS auth_event;
+DROP FUNCTION IF EXISTS reject_auth_event_update();
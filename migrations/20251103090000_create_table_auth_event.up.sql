@@ -0,0 +1,25 @@
+-- Add up migration script here
+CREATE TABLE auth_event (
+    id BIGSERIAL PRIMARY KEY,
+    account_id INT4 REFERENCES account(id) ON DELETE CASCADE,
+    event_type VARCHAR(32) NOT NULL,
+    detail VARCHAR(255),
+    client_ip VARCHAR(64) NOT NULL,
+    user_agent VARCHAR(512),
+    request_id VARCHAR(64) NOT NULL,
+    utc_create TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX account_id_auth_event_idx ON auth_event(account_id, utc_create DESC);
+
+-- Events are only ever appended. Rows still go away with their account when
+-- it is purged.
+CREATE FUNCTION reject_auth_event_update() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'auth_event is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER auth_event_append_only
+    BEFORE UPDATE ON auth_event
+    FOR EACH ROW EXECUTE FUNCTION reject_auth_event_update();
@@ -0,0 +1,10 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS window_start_login_failure_ip_idx;
+DROP INDEX IF EXISTS expiry_time_data_export_idx;
+DROP INDEX IF EXISTS expiry_time_oidc_login_state_idx;
+DROP INDEX IF EXISTS expiry_time_login_link_token_idx;
+DROP INDEX IF EXISTS expiry_time_login_challenge_idx;
+DROP INDEX IF EXISTS expiry_time_email_verification_token_idx;
+DROP INDEX IF EXISTS expiry_time_password_reset_token_idx;
+DROP INDEX IF EXISTS expiry_time_refresh_token_idx;
+DROP INDEX IF EXISTS expiry_time_session_idx;
@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE INDEX expiry_time_session_idx ON session(expiry_time);
+CREATE INDEX expiry_time_refresh_token_idx ON refresh_token(expiry_time);
+CREATE INDEX expiry_time_password_reset_token_idx ON password_reset_token(expiry_time);
+CREATE INDEX expiry_time_email_verification_token_idx ON email_verification_token(expiry_time);
+CREATE INDEX expiry_time_login_challenge_idx ON login_challenge(expiry_time);
+CREATE INDEX expiry_time_login_link_token_idx ON login_link_token(expiry_time);
+CREATE INDEX expiry_time_oidc_login_state_idx ON oidc_login_state(expiry_time);
+CREATE INDEX expiry_time_data_export_idx ON data_export(expiry_time);
+CREATE INDEX window_start_login_failure_ip_idx ON login_failure_ip(window_start);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS lower_email_account_idx;
+ALTER TABLE account ADD CONSTRAINT account_email_key UNIQUE (email);
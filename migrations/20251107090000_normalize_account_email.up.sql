@@ -0,0 +1,49 @@
+-- Add up migration script here
+-- Same normalization as the application: trimmed, NFC, lowercase domain.
+-- NFC needs a UTF8 database; other encodings cannot hold the characters it
+-- would change anyway.
+CREATE FUNCTION pg_temp.normalized_email(email TEXT) RETURNS TEXT AS $$
+DECLARE
+    trimmed TEXT := regexp_replace(email, '^\s+|\s+$', '', 'g');
+BEGIN
+    IF current_setting('server_encoding') = 'UTF8' THEN
+        trimmed := normalize(trimmed, NFC);
+    END IF;
+    RETURN regexp_replace(trimmed, '@[^@]*$', '')
+        || coalesce(lower(substring(trimmed FROM '@[^@]*$')), '');
+END;
+$$ LANGUAGE plpgsql;
+
+-- Emails are compared case-insensitively from now on. Accounts whose emails
+-- only differ in case, surrounding whitespace or Unicode composition would
+-- become the same identity, so they have to be merged or renamed by hand
+-- first; the migration stops and lists them.
+DO $$
+DECLARE
+    collisions TEXT;
+BEGIN
+    SELECT string_agg(format('%s (accounts %s)', email_key, account_ids), '; ')
+    INTO collisions
+    FROM (
+        SELECT lower(pg_temp.normalized_email(email)) AS email_key,
+               string_agg(id::text, ', ' ORDER BY id) AS account_ids
+        FROM account
+        GROUP BY 1
+        HAVING count(*) > 1
+    ) colliding;
+
+    IF collisions IS NOT NULL THEN
+        RAISE EXCEPTION 'Accounts share an email that differs only in case or spacing: %. '
+            'Merge or rename them, then start the server again to rerun this migration.', collisions;
+    END IF;
+END
+$$;
+
+UPDATE account
+SET email = pg_temp.normalized_email(email), utc_modified = now()
+WHERE email <> pg_temp.normalized_email(email);
+
+ALTER TABLE account DROP CONSTRAINT account_email_key;
+CREATE UNIQUE INDEX lower_email_account_idx ON account(lower(email));
+
+DROP FUNCTION pg_temp.normalized_email(TEXT);
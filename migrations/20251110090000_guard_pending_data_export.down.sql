@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS pending_account_id_data_export_idx;
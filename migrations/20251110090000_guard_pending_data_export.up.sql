@@ -0,0 +1,11 @@
+-- Add up migration script here
+-- Only the newest pending export of an account can still be building.
+UPDATE data_export SET status = 'failed', utc_modified = now()
+WHERE status = 'pending'
+  AND id NOT IN (
+      SELECT DISTINCT ON (account_id) id FROM data_export
+      WHERE status = 'pending'
+      ORDER BY account_id, utc_create DESC
+  );
+
+CREATE UNIQUE INDEX pending_account_id_data_export_idx ON data_export(account_id) WHERE status = 'pending';
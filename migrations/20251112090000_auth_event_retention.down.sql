@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE login_failure_ip DROP COLUMN IF EXISTS rejection_recorded;
+
+DROP INDEX IF EXISTS utc_create_auth_event_idx;
@@ -0,0 +1,8 @@
+-- Add up migration script here
+-- Events stay append-only. They go away with their account when it is
+-- purged, and otherwise once they are older than the retention, which the
+-- expired row purge finds through this index.
+CREATE INDEX utc_create_auth_event_idx ON auth_event(utc_create);
+
+-- A blocked IP gets one audit row per window, not one per rejected attempt.
+ALTER TABLE login_failure_ip ADD COLUMN rejection_recorded BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,22 @@
+use anyhow::Context;
+use sqlx::PgPool;
+
+use crate::{
+    config::Command,
+    services::{handler::account::set_account_role, utils::email::normalize_email},
+};
+
+pub async fn run_command(command: Command, db: &PgPool) -> anyhow::Result<()> {
+    match command {
+        Command::Promote { email, role } => {
+            let email = normalize_email(&email);
+            set_account_role(db, &email, role)
+                .await
+                .map_err(|err| anyhow::anyhow!("{:?}", err))
+                .context("cannot change account role")?;
+            println!("{} is now {:?}", email, role);
+        }
+    }
+
+    Ok(())
+}
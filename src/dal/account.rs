@@ -1,5 +1,28 @@
 use chrono::{DateTime, Utc};
-use sqlx::{PgPool, prelude::FromRow};
+use sqlx::{PgConnection, PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// Roles are ordered: a role grants everything the roles before it grant.
+#[derive(
+    sqlx::Type,
+    serde::Serialize,
+    clap::ValueEnum,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[sqlx(type_name = "account_role", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum AccountRole {
+    User,
+    Admin,
+}
 
 #[derive(FromRow, Debug)]
 pub struct Account {
@@ -8,43 +31,279 @@ pub struct Account {
     pub password: String,
     pub utc_create: DateTime<Utc>,
     pub utc_modified: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub failed_login_count: i32,
+    pub lockout_count: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub delete_after: Option<DateTime<Utc>>,
+    pub role: AccountRole,
+    pub disabled_at: Option<DateTime<Utc>>,
 }
 
+/// Emails match case-insensitively, as the unique index on `lower(email)`
+/// compares them.
 pub async fn fetch_account_by_email(
     pool: &PgPool,
     email: &str,
 ) -> Result<Option<Account>, sqlx::Error> {
-    let account: Option<Account> = sqlx::query_as("SELECT * FROM account WHERE email = $1;")
+    let statement = "SELECT * FROM account WHERE lower(email) = lower($1);";
+    let account: Option<Account> = sqlx::query_as(statement)
         .bind(email)
         .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(account)
+}
+
+pub async fn fetch_account_by_id(pool: &PgPool, id: i32) -> Result<Option<Account>, sqlx::Error> {
+    let statement = "SELECT * FROM account WHERE id = $1;";
+    let account: Option<Account> = sqlx::query_as(statement)
+        .bind(id)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
         .await?;
     Ok(account)
 }
 
-pub async fn insert_account(pool: &PgPool, email: &str, password: &str) -> Result<(), sqlx::Error> {
+pub async fn insert_account<'e, E: PgExecutor<'e>>(
+    executor: E,
+    email: &str,
+    password: &str,
+) -> Result<i32, sqlx::Error> {
     let now = Utc::now().naive_utc(); // This is UTC time
-    sqlx::query(
-        "INSERT INTO account(email, password, utc_create, utc_modified) VALUES ($1, $2, $3, $4);",
-    )
-    .bind(email)
-    .bind(password)
-    .bind(now)
-    .bind(now)
-    .execute(pool)
-    .await?;
+    let statement = "INSERT INTO account(email, password, utc_create, utc_modified) VALUES ($1, $2, $3, $4) RETURNING id;";
+    let id: i32 = sqlx::query_scalar(statement)
+        .bind(email)
+        .bind(password)
+        .bind(now)
+        .bind(now)
+        .fetch_one(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(id)
+}
+
+pub async fn update_account_password<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: i32,
+    password: &str,
+) -> Result<(), sqlx::Error> {
+    let statement = "UPDATE account SET password = $1, utc_modified = $2 WHERE id = $3;";
+    sqlx::query(statement)
+        .bind(password)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn mark_account_verified<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: i32,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE account SET verified_at = $1, utc_modified = $1 WHERE id = $2 AND verified_at IS NULL;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
     Ok(())
 }
 
-pub async fn fetch_account_by_email_and_password(
+/// Counts a failed login. Every `max_attempts` consecutive failures lock the
+/// account for `base_seconds * 2^lockouts`, capped at `max_seconds`, and
+/// start counting again. The exponent stops at 30 so the power cannot
+/// overflow. Returns the lock expiry if this failure set one.
+pub async fn record_account_login_failure(
     pool: &PgPool,
-    email: &str,
-    password: &str,
-) -> Result<Option<Account>, sqlx::Error> {
-    let account: Option<Account> =
-        sqlx::query_as("SELECT * FROM account WHERE email = $1 AND password = $2;")
-            .bind(email)
-            .bind(password)
-            .fetch_optional(pool)
-            .await?;
-    Ok(account)
+    id: i32,
+    max_attempts: i32,
+    base_seconds: i64,
+    max_seconds: i64,
+) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE account SET \
+                     failed_login_count = CASE WHEN failed_login_count + 1 >= $2 THEN 0 ELSE failed_login_count + 1 END, \
+                     lockout_count = CASE WHEN failed_login_count + 1 >= $2 THEN lockout_count + 1 ELSE lockout_count END, \
+                     locked_until = CASE WHEN failed_login_count + 1 >= $2 \
+                     THEN $3 + make_interval(secs => LEAST($4 * POWER(2, LEAST(lockout_count, 30)), $5)) ELSE locked_until END, \
+                     utc_modified = $3 \
+                     WHERE id = $1 RETURNING locked_until;";
+    let locked_until: Option<DateTime<Utc>> = sqlx::query_scalar(statement)
+        .bind(id)
+        .bind(max_attempts)
+        .bind(now)
+        .bind(base_seconds as f64)
+        .bind(max_seconds as f64)
+        .fetch_one(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(locked_until.filter(|until| *until > now))
+}
+
+pub async fn reset_account_login_failures(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    let statement = "UPDATE account SET failed_login_count = 0, lockout_count = 0, locked_until = NULL, utc_modified = $1 \
+                     WHERE id = $2 AND (failed_login_count <> 0 OR lockout_count <> 0 OR locked_until IS NOT NULL);";
+    sqlx::query(statement)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Marks the account for deletion at `delete_after`. Returns false when a
+/// deletion is already scheduled.
+pub async fn schedule_account_deletion<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: i32,
+    delete_after: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let statement = "UPDATE account SET delete_after = $1, utc_modified = $2 WHERE id = $3 AND delete_after IS NULL;";
+    let result = sqlx::query(statement)
+        .bind(delete_after)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Clears a scheduled deletion. Returns false when none was scheduled.
+pub async fn cancel_account_deletion(pool: &PgPool, id: i32) -> Result<bool, sqlx::Error> {
+    let statement = "UPDATE account SET delete_after = NULL, utc_modified = $1 WHERE id = $2 AND delete_after IS NOT NULL;";
+    let result = sqlx::query(statement)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Locks up to `limit` accounts whose grace window is over. Rows locked by
+/// another replica are skipped.
+pub async fn fetch_account_ids_due_for_deletion_for_update(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<i32>, sqlx::Error> {
+    let statement = "SELECT id FROM account WHERE delete_after <= $1 ORDER BY delete_after LIMIT $2 FOR UPDATE SKIP LOCKED;";
+    let ids: Vec<i32> = sqlx::query_scalar(statement)
+        .bind(now)
+        .bind(limit)
+        .fetch_all(conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(ids)
+}
+
+/// Hard-deletes accounts. Tables referencing `account` cascade.
+pub async fn delete_accounts_by_ids<'e, E: PgExecutor<'e>>(
+    executor: E,
+    ids: &[i32],
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM account WHERE id = ANY($1);";
+    let result = sqlx::query(statement)
+        .bind(ids)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn update_account_role<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: i32,
+    role: AccountRole,
+) -> Result<(), sqlx::Error> {
+    let statement = "UPDATE account SET role = $1, utc_modified = $2 WHERE id = $3;";
+    sqlx::query(statement)
+        .bind(role)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Disables the account, or re-enables it when `disabled_at` is `None`.
+/// Returns false when the account does not exist.
+pub async fn update_account_disabled_at<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: i32,
+    disabled_at: Option<DateTime<Utc>>,
+) -> Result<bool, sqlx::Error> {
+    let statement = "UPDATE account SET disabled_at = $1, utc_modified = $2 WHERE id = $3;";
+    let result = sqlx::query(statement)
+        .bind(disabled_at)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Filters shared by [`fetch_accounts_page`] and [`count_accounts`]. `email`
+/// is a case-insensitive substring; the creation bounds are inclusive.
+pub struct AccountQuery<'a> {
+    pub email: Option<&'a str>,
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+}
+
+const ACCOUNT_QUERY_FILTER: &str = "($1::text IS NULL OR email ILIKE '%' || $1 || '%' ESCAPE '\\') \
+     AND ($2::timestamptz IS NULL OR utc_create >= $2) \
+     AND ($3::timestamptz IS NULL OR utc_create <= $3)";
+
+pub async fn fetch_accounts_page(
+    pool: &PgPool,
+    query: &AccountQuery<'_>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<Account>, sqlx::Error> {
+    let statement = format!(
+        "SELECT * FROM account WHERE {} ORDER BY utc_create DESC, id DESC LIMIT $4 OFFSET $5;",
+        ACCOUNT_QUERY_FILTER
+    );
+    let accounts: Vec<Account> = sqlx::query_as(&statement)
+        .bind(query.email.map(escape_like))
+        .bind(query.created_from)
+        .bind(query.created_to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .instrument(query_span(&statement))
+        .await?;
+    Ok(accounts)
+}
+
+pub async fn count_accounts(pool: &PgPool, query: &AccountQuery<'_>) -> Result<i64, sqlx::Error> {
+    let statement = format!(
+        "SELECT COUNT(*) FROM account WHERE {};",
+        ACCOUNT_QUERY_FILTER
+    );
+    let count: i64 = sqlx::query_scalar(&statement)
+        .bind(query.email.map(escape_like))
+        .bind(query.created_from)
+        .bind(query.created_to)
+        .fetch_one(pool)
+        .instrument(query_span(&statement))
+        .await?;
+    Ok(count)
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// An external OIDC identity linked to an account. `subject` is the `sub`
+/// claim, unique per provider.
+#[derive(FromRow, Debug)]
+pub struct AccountIdentity {
+    pub id: i32,
+    pub account_id: i32,
+    pub provider: String,
+    pub subject: String,
+    pub email: String,
+    pub last_login_time: Option<DateTime<Utc>>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+pub async fn fetch_account_identity(
+    pool: &PgPool,
+    provider: &str,
+    subject: &str,
+) -> Result<Option<AccountIdentity>, sqlx::Error> {
+    let statement = "SELECT * FROM account_identity WHERE provider = $1 AND subject = $2;";
+    let identity: Option<AccountIdentity> = sqlx::query_as(statement)
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(identity)
+}
+
+/// Links the identity unless the subject is already linked, e.g. by a
+/// concurrent first login. Returns whether it was inserted.
+pub async fn insert_account_identity<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+    provider: &str,
+    subject: &str,
+    email: &str,
+) -> Result<bool, sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO account_identity(account_id, provider, subject, email, last_login_time, utc_create, utc_modified) VALUES ($1, $2, $3, $4, $5, $5, $5) \
+                     ON CONFLICT (provider, subject) DO NOTHING;";
+    let result = sqlx::query(statement)
+        .bind(account_id)
+        .bind(provider)
+        .bind(subject)
+        .bind(email)
+        .bind(now)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn touch_account_identity(
+    pool: &PgPool,
+    id: i32,
+    email: &str,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE account_identity SET email = $1, last_login_time = $2, utc_modified = $2 WHERE id = $3;";
+    sqlx::query(statement)
+        .bind(email)
+        .bind(now)
+        .bind(id)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_account_identities_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<AccountIdentity>, sqlx::Error> {
+    let statement = "SELECT * FROM account_identity WHERE account_id = $1 ORDER BY utc_create;";
+    let rows: Vec<AccountIdentity> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(rows)
+}
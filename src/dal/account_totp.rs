@@ -0,0 +1,99 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// The TOTP secret of an account, AES-GCM encrypted. `enabled_at` stays
+/// empty until the user proves the authenticator works.
+#[derive(FromRow, Debug)]
+pub struct AccountTotp {
+    pub account_id: i32,
+    pub secret_encrypted: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+pub async fn fetch_account_totp(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Option<AccountTotp>, sqlx::Error> {
+    let statement = "SELECT * FROM account_totp WHERE account_id = $1;";
+    let totp: Option<AccountTotp> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(totp)
+}
+
+/// Stores a fresh pending secret, replacing any earlier unconfirmed one.
+pub async fn upsert_pending_account_totp(
+    pool: &PgPool,
+    account_id: i32,
+    secret_encrypted: &str,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO account_totp(account_id, secret_encrypted, utc_create, utc_modified) VALUES ($1, $2, $3, $3) \
+                     ON CONFLICT (account_id) DO UPDATE SET secret_encrypted = $2, enabled_at = NULL, last_used_step = NULL, utc_modified = $3;";
+    sqlx::query(statement)
+        .bind(account_id)
+        .bind(secret_encrypted)
+        .bind(now)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn enable_account_totp<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+    used_step: i64,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE account_totp SET enabled_at = $1, last_used_step = $2, utc_modified = $1 WHERE account_id = $3;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(used_step)
+        .bind(account_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Records the step of an accepted code. Fails to update when a concurrent
+/// request already consumed this or a later step.
+pub async fn update_account_totp_last_used_step<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+    used_step: i64,
+) -> Result<bool, sqlx::Error> {
+    let statement = "UPDATE account_totp SET last_used_step = $1, utc_modified = $2 \
+                     WHERE account_id = $3 AND (last_used_step IS NULL OR last_used_step < $1);";
+    let result = sqlx::query(statement)
+        .bind(used_step)
+        .bind(Utc::now())
+        .bind(account_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Drops the TOTP enrollment of an account, pending or enabled.
+pub async fn delete_account_totp<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+) -> Result<(), sqlx::Error> {
+    let statement = "DELETE FROM account_totp WHERE account_id = $1;";
+    sqlx::query(statement)
+        .bind(account_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use sqlx::PgConnection;
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// Takes the session-level advisory lock `key` without waiting. Returns
+/// whether it was acquired; the lock stays with this connection until it is
+/// released or the connection closes.
+pub async fn try_advisory_lock(conn: &mut PgConnection, key: i64) -> Result<bool, sqlx::Error> {
+    let statement = "SELECT pg_try_advisory_lock($1);";
+    let acquired: bool = sqlx::query_scalar(statement)
+        .bind(key)
+        .fetch_one(conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(acquired)
+}
+
+pub async fn advisory_unlock(conn: &mut PgConnection, key: i64) -> Result<bool, sqlx::Error> {
+    let statement = "SELECT pg_advisory_unlock($1);";
+    let released: bool = sqlx::query_scalar(statement)
+        .bind(key)
+        .fetch_one(conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(released)
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// A long-lived key for machine clients. `key_hash` is the SHA-256 of the
+/// key, which is only shown when it is created; `key_prefix` lets the owner
+/// tell keys apart.
+#[derive(FromRow, Debug)]
+pub struct ApiKey {
+    pub id: i32,
+    pub account_id: i32,
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expiry_time: Option<DateTime<Utc>>,
+    pub last_used_time: Option<DateTime<Utc>>,
+    pub revoked_time: Option<DateTime<Utc>>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+pub struct NewApiKey<'a> {
+    pub account_id: i32,
+    pub name: &'a str,
+    pub key_prefix: &'a str,
+    pub key_hash: &'a str,
+    pub scopes: &'a [String],
+    pub expiry_time: Option<DateTime<Utc>>,
+}
+
+pub async fn insert_api_key(pool: &PgPool, key: &NewApiKey<'_>) -> Result<ApiKey, sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO api_key(account_id, name, key_prefix, key_hash, scopes, expiry_time, utc_create, utc_modified) \
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *;";
+    let api_key: ApiKey = sqlx::query_as(statement)
+        .bind(key.account_id)
+        .bind(key.name)
+        .bind(key.key_prefix)
+        .bind(key.key_hash)
+        .bind(key.scopes)
+        .bind(key.expiry_time)
+        .bind(now)
+        .bind(now)
+        .fetch_one(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(api_key)
+}
+
+pub async fn fetch_api_key_by_hash(
+    pool: &PgPool,
+    key_hash: &str,
+) -> Result<Option<ApiKey>, sqlx::Error> {
+    let statement = "SELECT * FROM api_key WHERE key_hash = $1;";
+    let api_key: Option<ApiKey> = sqlx::query_as(statement)
+        .bind(key_hash)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(api_key)
+}
+
+pub async fn fetch_api_keys_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<ApiKey>, sqlx::Error> {
+    let statement =
+        "SELECT * FROM api_key WHERE account_id = $1 ORDER BY utc_create DESC, id DESC;";
+    let api_keys: Vec<ApiKey> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(api_keys)
+}
+
+/// Returns false when the key does not exist, belongs to another account or
+/// is already revoked.
+pub async fn revoke_api_key(pool: &PgPool, id: i32, account_id: i32) -> Result<bool, sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE api_key SET revoked_time = $1, utc_modified = $1 WHERE id = $2 AND account_id = $3 AND revoked_time IS NULL;";
+    let result = sqlx::query(statement)
+        .bind(now)
+        .bind(id)
+        .bind(account_id)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn revoke_api_keys_by_account_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE api_key SET revoked_time = $1, utc_modified = $1 WHERE account_id = $2 AND revoked_time IS NULL;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(account_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Records a use of the key. Writes at most once per `granularity` so busy
+/// clients do not turn every request into an update.
+pub async fn touch_api_key(
+    pool: &PgPool,
+    id: i32,
+    granularity: Duration,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE api_key SET last_used_time = $1 WHERE id = $2 AND (last_used_time IS NULL OR last_used_time < $3);";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(id)
+        .bind(now - granularity)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// One row of the append-only authentication audit log. `account_id` is empty
+/// when the event could not be tied to an account, e.g. a login attempt for
+/// an unknown email.
+#[derive(FromRow, Debug)]
+pub struct AuthEvent {
+    pub id: i64,
+    pub account_id: Option<i32>,
+    pub event_type: String,
+    pub detail: Option<String>,
+    pub client_ip: String,
+    pub user_agent: Option<String>,
+    pub request_id: String,
+    pub utc_create: DateTime<Utc>,
+}
+
+pub struct NewAuthEvent<'a> {
+    pub account_id: Option<i32>,
+    pub event_type: &'a str,
+    pub detail: Option<&'a str>,
+    pub client_ip: &'a str,
+    pub user_agent: Option<&'a str>,
+    pub request_id: &'a str,
+}
+
+pub async fn insert_auth_event(pool: &PgPool, event: &NewAuthEvent<'_>) -> Result<(), sqlx::Error> {
+    let statement = "INSERT INTO auth_event(account_id, event_type, detail, client_ip, user_agent, request_id, utc_create) VALUES ($1, $2, $3, $4, $5, $6, $7);";
+    sqlx::query(statement)
+        .bind(event.account_id)
+        .bind(event.event_type)
+        .bind(event.detail)
+        .bind(event.client_ip)
+        .bind(event.user_agent)
+        .bind(event.request_id)
+        .bind(Utc::now())
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_auth_events_page(
+    pool: &PgPool,
+    account_id: i32,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<AuthEvent>, sqlx::Error> {
+    let statement = "SELECT * FROM auth_event WHERE account_id = $1 ORDER BY utc_create DESC, id DESC LIMIT $2 OFFSET $3;";
+    let events: Vec<AuthEvent> = sqlx::query_as(statement)
+        .bind(account_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(events)
+}
+
+pub async fn fetch_auth_events_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<AuthEvent>, sqlx::Error> {
+    let statement =
+        "SELECT * FROM auth_event WHERE account_id = $1 ORDER BY utc_create DESC, id DESC;";
+    let events: Vec<AuthEvent> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(events)
+}
+
+pub async fn count_auth_events(pool: &PgPool, account_id: i32) -> Result<i64, sqlx::Error> {
+    let statement = "SELECT COUNT(*) FROM auth_event WHERE account_id = $1;";
+    let count: i64 = sqlx::query_scalar(statement)
+        .bind(account_id)
+        .fetch_one(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(count)
+}
+
+/// Deletes events created before `cutoff`, the end of their retention.
+pub async fn delete_expired_auth_events<'e, E: PgExecutor<'e>>(
+    executor: E,
+    cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM auth_event WHERE id IN (SELECT id FROM auth_event WHERE utc_create < $1 LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(cutoff)
+        .bind(limit)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+pub const DATA_EXPORT_PENDING: &str = "pending";
+pub const DATA_EXPORT_READY: &str = "ready";
+pub const DATA_EXPORT_FAILED: &str = "failed";
+
+/// A personal data export job. `id` is the SHA-256 of the download token and
+/// also names the archive file.
+#[derive(FromRow, Debug)]
+pub struct DataExport {
+    pub id: String,
+    pub account_id: i32,
+    pub status: String,
+    pub ready_time: Option<DateTime<Utc>>,
+    pub expiry_time: Option<DateTime<Utc>>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+/// Inserts a pending export unless the account already has one, which the
+/// partial unique index on pending rows enforces. Returns whether it was
+/// inserted.
+pub async fn insert_data_export(
+    pool: &PgPool,
+    id: &str,
+    account_id: i32,
+) -> Result<bool, sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO data_export(id, account_id, status, utc_create, utc_modified) VALUES ($1, $2, $3, $4, $5) \
+                     ON CONFLICT (account_id) WHERE status = 'pending' DO NOTHING;";
+    let result = sqlx::query(statement)
+        .bind(id)
+        .bind(account_id)
+        .bind(DATA_EXPORT_PENDING)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Fails the account's pending exports created at or before `cutoff`: their
+/// build task died with a crash or shutdown and will never finish them.
+pub async fn fail_stale_data_exports(
+    pool: &PgPool,
+    account_id: i32,
+    cutoff: DateTime<Utc>,
+) -> Result<u64, sqlx::Error> {
+    let statement = "UPDATE data_export SET status = $1, utc_modified = $2 WHERE account_id = $3 AND status = $4 AND utc_create <= $5;";
+    let result = sqlx::query(statement)
+        .bind(DATA_EXPORT_FAILED)
+        .bind(Utc::now())
+        .bind(account_id)
+        .bind(DATA_EXPORT_PENDING)
+        .bind(cutoff)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn fetch_data_export_by_id(
+    pool: &PgPool,
+    id: &str,
+) -> Result<Option<DataExport>, sqlx::Error> {
+    let statement = "SELECT * FROM data_export WHERE id = $1;";
+    let export: Option<DataExport> = sqlx::query_as(statement)
+        .bind(id)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(export)
+}
+
+pub async fn fetch_latest_data_export(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Option<DataExport>, sqlx::Error> {
+    let statement =
+        "SELECT * FROM data_export WHERE account_id = $1 ORDER BY utc_create DESC LIMIT 1;";
+    let export: Option<DataExport> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(export)
+}
+
+pub async fn fetch_data_exports_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<DataExport>, sqlx::Error> {
+    let statement = "SELECT * FROM data_export WHERE account_id = $1 ORDER BY utc_create;";
+    let exports: Vec<DataExport> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(exports)
+}
+
+pub async fn fetch_data_export_ids_by_account_ids<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_ids: &[i32],
+) -> Result<Vec<String>, sqlx::Error> {
+    let statement = "SELECT id FROM data_export WHERE account_id = ANY($1);";
+    let ids: Vec<String> = sqlx::query_scalar(statement)
+        .bind(account_ids)
+        .fetch_all(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(ids)
+}
+
+pub async fn mark_data_export_ready(
+    pool: &PgPool,
+    id: &str,
+    expiry_time: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE data_export SET status = $1, ready_time = $2, expiry_time = $3, utc_modified = $2 WHERE id = $4;";
+    sqlx::query(statement)
+        .bind(DATA_EXPORT_READY)
+        .bind(now)
+        .bind(expiry_time)
+        .bind(id)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn mark_data_export_failed(pool: &PgPool, id: &str) -> Result<(), sqlx::Error> {
+    let statement = "UPDATE data_export SET status = $1, utc_modified = $2 WHERE id = $3;";
+    sqlx::query(statement)
+        .bind(DATA_EXPORT_FAILED)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Deletes up to `limit` exports whose download window closed at or before
+/// `now`, or that never got one and were requested at or before
+/// `unfinished_cutoff`, and returns their ids, so the archives can be removed
+/// from disk.
+pub async fn delete_expired_data_exports<'e, E: PgExecutor<'e>>(
+    executor: E,
+    now: DateTime<Utc>,
+    unfinished_cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<String>, sqlx::Error> {
+    let statement = "DELETE FROM data_export WHERE id IN (SELECT id FROM data_export \
+                     WHERE expiry_time <= $1 OR (expiry_time IS NULL AND utc_create <= $2) LIMIT $3) RETURNING id;";
+    let ids: Vec<String> = sqlx::query_scalar(statement)
+        .bind(now)
+        .bind(unfinished_cutoff)
+        .bind(limit)
+        .fetch_all(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(ids)
+}
@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// A pending second login step. `id` is the SHA-256 of the challenge token
+/// returned after the password was accepted.
+#[derive(FromRow, Debug)]
+pub struct LoginChallenge {
+    pub id: String,
+    pub account_id: i32,
+    pub expiry_time: DateTime<Utc>,
+    pub used_time: Option<DateTime<Utc>>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+pub async fn insert_login_challenge(
+    pool: &PgPool,
+    id: &str,
+    account_id: i32,
+    expiry_time: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO login_challenge(id, account_id, expiry_time, utc_create, utc_modified) VALUES ($1, $2, $3, $4, $5);";
+    sqlx::query(statement)
+        .bind(id)
+        .bind(account_id)
+        .bind(expiry_time)
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_login_challenge_for_update(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<LoginChallenge>, sqlx::Error> {
+    let statement = "SELECT * FROM login_challenge WHERE id = $1 FOR UPDATE;";
+    let challenge: Option<LoginChallenge> = sqlx::query_as(statement)
+        .bind(id)
+        .fetch_optional(conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(challenge)
+}
+
+pub async fn mark_login_challenge_used<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: &str,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE login_challenge SET used_time = $1, utc_modified = $1 WHERE id = $2;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_login_challenges_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<LoginChallenge>, sqlx::Error> {
+    let statement = "SELECT * FROM login_challenge WHERE account_id = $1 ORDER BY utc_create;";
+    let rows: Vec<LoginChallenge> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(rows)
+}
+
+/// Deletes up to `limit` rows that expired at or before `now` and returns how
+/// many were deleted.
+pub async fn delete_expired_login_challenges<'e, E: PgExecutor<'e>>(
+    executor: E,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM login_challenge WHERE id IN (SELECT id FROM login_challenge WHERE expiry_time <= $1 LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(now)
+        .bind(limit)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
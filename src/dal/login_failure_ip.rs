@@ -0,0 +1,87 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// Failed logins from one client IP within a fixed window starting at
+/// `window_start`.
+#[derive(FromRow, Debug)]
+pub struct LoginFailureIp {
+    pub ip: String,
+    pub failed_count: i32,
+    pub window_start: DateTime<Utc>,
+    pub rejection_recorded: bool,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+pub async fn fetch_login_failure_ip(
+    pool: &PgPool,
+    ip: &str,
+) -> Result<Option<LoginFailureIp>, sqlx::Error> {
+    let statement = "SELECT * FROM login_failure_ip WHERE ip = $1;";
+    let failure: Option<LoginFailureIp> = sqlx::query_as(statement)
+        .bind(ip)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(failure)
+}
+
+/// Counts one more failure for the IP, starting a new window when the
+/// previous one has elapsed.
+pub async fn record_login_failure_ip(
+    pool: &PgPool,
+    ip: &str,
+    window_seconds: i64,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO login_failure_ip(ip, failed_count, window_start, utc_create, utc_modified) VALUES ($1, 1, $2, $2, $2) \
+                     ON CONFLICT (ip) DO UPDATE SET \
+                     failed_count = CASE WHEN login_failure_ip.window_start + make_interval(secs => $3) <= $2 THEN 1 ELSE login_failure_ip.failed_count + 1 END, \
+                     window_start = CASE WHEN login_failure_ip.window_start + make_interval(secs => $3) <= $2 THEN $2 ELSE login_failure_ip.window_start END, \
+                     rejection_recorded = login_failure_ip.rejection_recorded AND login_failure_ip.window_start + make_interval(secs => $3) > $2, \
+                     utc_modified = $2;";
+    sqlx::query(statement)
+        .bind(ip)
+        .bind(now)
+        .bind(window_seconds as f64)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Flags that the current window's first rejection was written to the audit
+/// log. Returns whether this call set the flag.
+pub async fn mark_login_failure_ip_rejection_recorded(
+    pool: &PgPool,
+    ip: &str,
+) -> Result<bool, sqlx::Error> {
+    let statement = "UPDATE login_failure_ip SET rejection_recorded = true, utc_modified = $1 WHERE ip = $2 AND NOT rejection_recorded;";
+    let result = sqlx::query(statement)
+        .bind(Utc::now())
+        .bind(ip)
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+/// Deletes up to `limit` counters whose window started at or before `cutoff`;
+/// the next failure from such an IP starts a new window anyway.
+pub async fn delete_stale_login_failure_ips<'e, E: PgExecutor<'e>>(
+    executor: E,
+    cutoff: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM login_failure_ip WHERE ip IN (SELECT ip FROM login_failure_ip WHERE window_start <= $1 LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(cutoff)
+        .bind(limit)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,21 @@
+use sqlx::{PgPool, migrate::Migrator};
+
+/// Migrations embedded in the binary, so the database is migrated on startup
+/// and readiness can tell whether it is behind.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+// Unlike the rest of `dal`, these queries get no span: they run on every
+// readiness probe and would bury real traces under single-span ones.
+
+pub async fn fetch_applied_migration_versions(pool: &PgPool) -> Result<Vec<i64>, sqlx::Error> {
+    let versions: Vec<i64> =
+        sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success;")
+            .fetch_all(pool)
+            .await?;
+    Ok(versions)
+}
+
+pub async fn ping(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT 1;").execute(pool).await?;
+    Ok(())
+}
@@ -1,2 +1,38 @@
 pub mod account;
-pub mod session;
\ No newline at end of file
+pub mod account_identity;
+pub mod account_totp;
+pub mod advisory_lock;
+pub mod api_key;
+pub mod auth_event;
+pub mod data_export;
+pub mod email_verification_token;
+pub mod login_challenge;
+pub mod login_failure_ip;
+pub mod login_link_token;
+pub mod migration;
+pub mod oidc_login_state;
+pub mod password_history;
+pub mod password_reset_token;
+pub mod refresh_token;
+pub mod session;
+pub mod totp_recovery_code;
+
+use tracing::Span;
+
+/// Client span of one query, named after its SQL operation. The statement is
+/// recorded with its placeholders; bound values never reach the span.
+pub fn query_span(statement: &str) -> Span {
+    let operation = statement
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    tracing::info_span!(
+        "db_query",
+        otel.name = %operation,
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.operation = %operation,
+        db.statement = statement,
+    )
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// An authorization request sent to an OIDC provider and not yet answered.
+/// `id` is the SHA-256 of the `state` parameter.
+#[derive(FromRow, Debug)]
+pub struct OidcLoginState {
+    pub id: String,
+    pub provider: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    pub expiry_time: DateTime<Utc>,
+    pub utc_create: DateTime<Utc>,
+}
+
+pub struct NewOidcLoginState<'a> {
+    pub id: &'a str,
+    pub provider: &'a str,
+    pub code_verifier: &'a str,
+    pub nonce: &'a str,
+    pub expiry_time: DateTime<Utc>,
+}
+
+pub async fn insert_oidc_login_state(
+    pool: &PgPool,
+    state: &NewOidcLoginState<'_>,
+) -> Result<(), sqlx::Error> {
+    let statement = "INSERT INTO oidc_login_state(id, provider, code_verifier, nonce, expiry_time, utc_create) VALUES ($1, $2, $3, $4, $5, $6);";
+    sqlx::query(statement)
+        .bind(state.id)
+        .bind(state.provider)
+        .bind(state.code_verifier)
+        .bind(state.nonce)
+        .bind(state.expiry_time)
+        .bind(Utc::now())
+        .execute(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Removes and returns the state, so a callback can only be answered once.
+pub async fn take_oidc_login_state(
+    pool: &PgPool,
+    id: &str,
+    provider: &str,
+) -> Result<Option<OidcLoginState>, sqlx::Error> {
+    let statement = "DELETE FROM oidc_login_state WHERE id = $1 AND provider = $2 RETURNING *;";
+    let state: Option<OidcLoginState> = sqlx::query_as(statement)
+        .bind(id)
+        .bind(provider)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(state)
+}
+
+/// Deletes up to `limit` rows that expired at or before `now` and returns how
+/// many were deleted.
+pub async fn delete_expired_oidc_login_states<'e, E: PgExecutor<'e>>(
+    executor: E,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM oidc_login_state WHERE id IN (SELECT id FROM oidc_login_state WHERE expiry_time <= $1 LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(now)
+        .bind(limit)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
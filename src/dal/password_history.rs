@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// A password hash the account used before its current one.
+#[derive(FromRow, Debug)]
+pub struct PasswordHistory {
+    pub id: i32,
+    pub account_id: i32,
+    pub password: String,
+    pub utc_create: DateTime<Utc>,
+}
+
+/// Copies the account's current password hash into its history. Call it in the
+/// same transaction, right before the password is replaced.
+pub async fn archive_account_password<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+) -> Result<(), sqlx::Error> {
+    let statement = "INSERT INTO password_history(account_id, password, utc_create) SELECT id, password, $2 FROM account WHERE id = $1;";
+    sqlx::query(statement)
+        .bind(account_id)
+        .bind(Utc::now())
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Deletes all but the `keep` newest history entries of the account.
+pub async fn trim_password_history<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+    keep: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM password_history WHERE account_id = $1 AND id NOT IN \
+                     (SELECT id FROM password_history WHERE account_id = $1 ORDER BY utc_create DESC, id DESC LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(account_id)
+        .bind(keep)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn fetch_recent_password_history(
+    pool: &PgPool,
+    account_id: i32,
+    limit: i64,
+) -> Result<Vec<PasswordHistory>, sqlx::Error> {
+    let statement = "SELECT * FROM password_history WHERE account_id = $1 ORDER BY utc_create DESC, id DESC LIMIT $2;";
+    let history: Vec<PasswordHistory> = sqlx::query_as(statement)
+        .bind(account_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(history)
+}
+
+pub async fn fetch_password_history_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<PasswordHistory>, sqlx::Error> {
+    let statement = "SELECT * FROM password_history WHERE account_id = $1 ORDER BY utc_create, id;";
+    let rows: Vec<PasswordHistory> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(rows)
+}
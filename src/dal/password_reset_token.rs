@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// A single-use password reset token. `id` is the SHA-256 of the token
+/// mailed to the user.
+#[derive(FromRow, Debug)]
+pub struct PasswordResetToken {
+    pub id: String,
+    pub account_id: i32,
+    pub expiry_time: DateTime<Utc>,
+    pub used_time: Option<DateTime<Utc>>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+pub async fn insert_password_reset_token<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: &str,
+    account_id: i32,
+    expiry_time: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO password_reset_token(id, account_id, expiry_time, utc_create, utc_modified) VALUES ($1, $2, $3, $4, $5);";
+    sqlx::query(statement)
+        .bind(id)
+        .bind(account_id)
+        .bind(expiry_time)
+        .bind(now)
+        .bind(now)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_latest_password_reset_token(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Option<PasswordResetToken>, sqlx::Error> {
+    let statement = "SELECT * FROM password_reset_token WHERE account_id = $1 ORDER BY utc_create DESC LIMIT 1;";
+    let token: Option<PasswordResetToken> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(token)
+}
+
+pub async fn fetch_password_reset_token_for_update(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<PasswordResetToken>, sqlx::Error> {
+    let statement = "SELECT * FROM password_reset_token WHERE id = $1 FOR UPDATE;";
+    let token: Option<PasswordResetToken> = sqlx::query_as(statement)
+        .bind(id)
+        .fetch_optional(conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(token)
+}
+
+/// Burns every outstanding reset token of the account, not only the one used.
+pub async fn mark_password_reset_tokens_used<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE password_reset_token SET used_time = $1, utc_modified = $1 WHERE account_id = $2 AND used_time IS NULL;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(account_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_password_reset_tokens_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<PasswordResetToken>, sqlx::Error> {
+    let statement = "SELECT * FROM password_reset_token WHERE account_id = $1 ORDER BY utc_create;";
+    let rows: Vec<PasswordResetToken> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(rows)
+}
+
+/// Deletes up to `limit` rows that expired at or before `now` and returns how
+/// many were deleted.
+pub async fn delete_expired_password_reset_tokens<'e, E: PgExecutor<'e>>(
+    executor: E,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM password_reset_token WHERE id IN (SELECT id FROM password_reset_token WHERE expiry_time <= $1 LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(now)
+        .bind(limit)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+/// A refresh token row. `id` is the SHA-256 of the token handed to the
+/// client; every rotation of one login shares the same `family_id`.
+#[derive(FromRow, Debug)]
+pub struct RefreshToken {
+    pub id: String,
+    pub family_id: String,
+    pub account_id: i32,
+    pub session_id: String,
+    pub expiry_time: DateTime<Utc>,
+    pub rotated_time: Option<DateTime<Utc>>,
+    pub revoked_time: Option<DateTime<Utc>>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+pub async fn insert_refresh_token<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: &str,
+    family_id: &str,
+    account_id: i32,
+    session_id: &str,
+    expiry_time: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO refresh_token(id, family_id, account_id, session_id, expiry_time, utc_create, utc_modified) VALUES ($1, $2, $3, $4, $5, $6, $7);";
+    sqlx::query(statement)
+        .bind(id)
+        .bind(family_id)
+        .bind(account_id)
+        .bind(session_id)
+        .bind(expiry_time)
+        .bind(now)
+        .bind(now)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Locks the row so two concurrent refreshes cannot both rotate it.
+pub async fn fetch_refresh_token_for_update(
+    conn: &mut PgConnection,
+    id: &str,
+) -> Result<Option<RefreshToken>, sqlx::Error> {
+    let statement = "SELECT * FROM refresh_token WHERE id = $1 FOR UPDATE;";
+    let token: Option<RefreshToken> = sqlx::query_as(statement)
+        .bind(id)
+        .fetch_optional(conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(token)
+}
+
+pub async fn mark_refresh_token_rotated<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: &str,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE refresh_token SET rotated_time = $1, utc_modified = $1 WHERE id = $2;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Revokes every token in the family and deletes the sessions they issued.
+pub async fn revoke_refresh_token_family(
+    conn: &mut PgConnection,
+    family_id: &str,
+) -> Result<u64, sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE refresh_token SET revoked_time = $1, utc_modified = $1 WHERE family_id = $2 AND revoked_time IS NULL;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(family_id)
+        .execute(&mut *conn)
+        .instrument(query_span(statement))
+        .await?;
+
+    let statement = "DELETE FROM session WHERE id IN (SELECT session_id FROM refresh_token WHERE family_id = $1);";
+    let result = sqlx::query(statement)
+        .bind(family_id)
+        .execute(&mut *conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn revoke_refresh_tokens_by_session_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    session_id: &str,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE refresh_token SET revoked_time = $1, utc_modified = $1 WHERE family_id IN (SELECT family_id FROM refresh_token WHERE session_id = $2) AND revoked_time IS NULL;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(session_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn revoke_refresh_tokens_by_account_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE refresh_token SET revoked_time = $1, utc_modified = $1 WHERE account_id = $2 AND revoked_time IS NULL;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(account_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Revokes every refresh token family of the account except the one the given
+/// session belongs to.
+pub async fn revoke_other_refresh_tokens_by_account_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+    keep_session_id: &str,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE refresh_token SET revoked_time = $1, utc_modified = $1 WHERE account_id = $2 AND family_id NOT IN (SELECT family_id FROM refresh_token WHERE session_id = $3) AND revoked_time IS NULL;";
+    sqlx::query(statement)
+        .bind(now)
+        .bind(account_id)
+        .bind(keep_session_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_refresh_tokens_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<RefreshToken>, sqlx::Error> {
+    let statement = "SELECT * FROM refresh_token WHERE account_id = $1 ORDER BY utc_create;";
+    let rows: Vec<RefreshToken> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(rows)
+}
+
+/// Deletes up to `limit` rows that expired at or before `now` and returns how
+/// many were deleted.
+pub async fn delete_expired_refresh_tokens<'e, E: PgExecutor<'e>>(
+    executor: E,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM refresh_token WHERE id IN (SELECT id FROM refresh_token WHERE expiry_time <= $1 LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(now)
+        .bind(limit)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
-use sqlx::prelude::FromRow;
+use sqlx::{PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
 
 #[derive(FromRow, Debug)]
 pub struct Session {
@@ -10,3 +13,132 @@ pub struct Session {
     pub utc_modified: DateTime<Utc>,
 }
 
+pub async fn insert_session<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: &str,
+    account_id: i32,
+    expiry_time: DateTime<Utc>,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now();
+    let statement = "INSERT INTO session(id, account_id, expiry_time, utc_create, utc_modified) VALUES ($1, $2, $3, $4, $5);";
+    sqlx::query(statement)
+        .bind(id)
+        .bind(account_id)
+        .bind(expiry_time)
+        .bind(now)
+        .bind(now)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+pub async fn fetch_session_by_id(pool: &PgPool, id: &str) -> Result<Option<Session>, sqlx::Error> {
+    let statement = "SELECT * FROM session WHERE id = $1;";
+    let session: Option<Session> = sqlx::query_as(statement)
+        .bind(id)
+        .fetch_optional(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(session)
+}
+
+pub async fn fetch_active_sessions_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<Session>, sqlx::Error> {
+    let statement = "SELECT * FROM session WHERE account_id = $1 AND expiry_time > $2 ORDER BY utc_create DESC;";
+    let sessions: Vec<Session> = sqlx::query_as(statement)
+        .bind(account_id)
+        .bind(Utc::now())
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(sessions)
+}
+
+pub async fn delete_session_by_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    id: &str,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM session WHERE id = $1;";
+    let result = sqlx::query(statement)
+        .bind(id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn delete_sessions_by_account_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM session WHERE account_id = $1;";
+    let result = sqlx::query(statement)
+        .bind(account_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn delete_other_sessions_by_account_id<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+    keep_session_id: &str,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM session WHERE account_id = $1 AND id <> $2;";
+    let result = sqlx::query(statement)
+        .bind(account_id)
+        .bind(keep_session_id)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+/// `session` has no foreign key to `account`, so account deletion removes
+/// its sessions explicitly.
+pub async fn delete_sessions_by_account_ids<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_ids: &[i32],
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM session WHERE account_id = ANY($1);";
+    let result = sqlx::query(statement)
+        .bind(account_ids)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn fetch_sessions_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<Session>, sqlx::Error> {
+    let statement = "SELECT * FROM session WHERE account_id = $1 ORDER BY utc_create;";
+    let rows: Vec<Session> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(rows)
+}
+
+/// Deletes up to `limit` rows that expired at or before `now` and returns how
+/// many were deleted.
+pub async fn delete_expired_sessions<'e, E: PgExecutor<'e>>(
+    executor: E,
+    now: DateTime<Utc>,
+    limit: i64,
+) -> Result<u64, sqlx::Error> {
+    let statement = "DELETE FROM session WHERE id IN (SELECT id FROM session WHERE expiry_time <= $1 LIMIT $2);";
+    let result = sqlx::query(statement)
+        .bind(now)
+        .bind(limit)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected())
+}
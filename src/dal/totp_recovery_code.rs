@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool, prelude::FromRow};
+use tracing::Instrument;
+
+use crate::dal::query_span;
+
+#[derive(FromRow, Debug)]
+pub struct TotpRecoveryCode {
+    pub id: i32,
+    pub account_id: i32,
+    pub code_hash: String,
+    pub used_time: Option<DateTime<Utc>>,
+    pub utc_create: DateTime<Utc>,
+    pub utc_modified: DateTime<Utc>,
+}
+
+/// Replaces every recovery code of the account with the given hashes.
+pub async fn replace_totp_recovery_codes(
+    conn: &mut PgConnection,
+    account_id: i32,
+    code_hashes: &[String],
+) -> Result<(), sqlx::Error> {
+    let statement = "DELETE FROM totp_recovery_code WHERE account_id = $1;";
+    sqlx::query(statement)
+        .bind(account_id)
+        .execute(&mut *conn)
+        .instrument(query_span(statement))
+        .await?;
+
+    let now = Utc::now();
+    let statement = "INSERT INTO totp_recovery_code(account_id, code_hash, utc_create, utc_modified) \
+                     SELECT $1, code_hash, $3, $3 FROM UNNEST($2::VARCHAR[]) AS code_hash;";
+    sqlx::query(statement)
+        .bind(account_id)
+        .bind(code_hashes)
+        .bind(now)
+        .execute(&mut *conn)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(())
+}
+
+/// Marks an unused recovery code as used. Returns whether one matched.
+pub async fn use_totp_recovery_code<'e, E: PgExecutor<'e>>(
+    executor: E,
+    account_id: i32,
+    code_hash: &str,
+) -> Result<bool, sqlx::Error> {
+    let now = Utc::now();
+    let statement = "UPDATE totp_recovery_code SET used_time = $1, utc_modified = $1 \
+                     WHERE account_id = $2 AND code_hash = $3 AND used_time IS NULL;";
+    let result = sqlx::query(statement)
+        .bind(now)
+        .bind(account_id)
+        .bind(code_hash)
+        .execute(executor)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+pub async fn fetch_totp_recovery_codes_by_account_id(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<Vec<TotpRecoveryCode>, sqlx::Error> {
+    let statement = "SELECT * FROM totp_recovery_code WHERE account_id = $1 ORDER BY id;";
+    let rows: Vec<TotpRecoveryCode> = sqlx::query_as(statement)
+        .bind(account_id)
+        .fetch_all(pool)
+        .instrument(query_span(statement))
+        .await?;
+    Ok(rows)
+}
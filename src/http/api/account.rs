@@ -1,20 +1,33 @@
-use axum::{Extension, Router, routing::post};
+use axum::{
+    Extension, Router,
+    extract::Query,
+    routing::{get, post},
+};
 
 use crate::{
     http::{
         context::{ApiContext, RequestContext},
         request::{
-            account::{LoginRequest, RegisterRequest},
+            account::{LoginRequest, RefreshRequest, RegisterRequest},
+            auth_account::AuthAccount,
+            auth_session::AuthSession,
+            page::PageQuery,
             safe_json::SafeJson,
         },
         result::{
-            account::{LoginResult, RegisterResult},
+            account::{ActivityResult, LoginResult, ProfileResult, RefreshResult, RegisterResult},
             app_result::{ApiResponse, AppResult, HttpError},
+            session::{LogoutResult, SessionsResult},
         },
         utils::{error::HttpErrorCase, scenario::HttpScenario},
     },
     services::{
-        handler::account::{login_user, register_user},
+        handler::{
+            account::{login_user, register_user},
+            auth_event::list_auth_events,
+            session::{list_active_sessions, refresh_session, revoke_all_sessions, revoke_session},
+        },
+        model::{account::AccountProfile, api_key::ApiKeyScope},
         utils::error::AppError,
     },
 };
@@ -23,6 +36,12 @@ pub fn router() -> Router {
     Router::new()
         .route("/account/register", post(handle_register_user))
         .route("/account/login", post(handle_login_user))
+        .route("/account/me", get(handle_get_profile))
+        .route("/account/logout", post(handle_logout))
+        .route("/account/logout-all", post(handle_logout_all))
+        .route("/account/sessions", get(handle_list_sessions))
+        .route("/account/refresh", post(handle_refresh_session))
+        .route("/account/activity", get(handle_list_activity))
 }
 
 async fn handle_register_user(
@@ -30,31 +49,38 @@ async fn handle_register_user(
     request_ctx: Extension<RequestContext>,
     SafeJson(payload): SafeJson<RegisterRequest>,
 ) -> AppResult<RegisterResult> {
-    let saved_account = register_user(&ctx.db, &payload.email, &payload.password)
-        .await
-        .map_err(|err| match err {
-            AppError::EmailRegistered { account } => HttpError {
-                status: 400,
-                scenario: HttpScenario::Register,
-                case: HttpErrorCase::ZeroThree,
-                error_log: format!("Email already registered: {}", account.email),
-                output: String::from("Email already registered"),
-            },
-            AppError::SqlxError { msg } => HttpError {
-                status: 500,
-                scenario: HttpScenario::Register,
-                case: HttpErrorCase::ZeroOne,
-                error_log: msg,
-                output: String::from("Internal Server Error"),
-            },
-            other => HttpError {
-                status: 500,
-                scenario: HttpScenario::Register,
-                case: HttpErrorCase::ZeroOne,
-                error_log: format!("Unexpected error: {:?}", other),
-                output: String::from("Internal Server error"),
-            },
-        })?;
+    let saved_account = register_user(
+        &ctx.db,
+        &ctx.config,
+        ctx.mailer.as_ref(),
+        &request_ctx.origin(),
+        &payload.email,
+        &payload.password,
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::EmailRegistered { account } => HttpError {
+            status: 400,
+            scenario: HttpScenario::Register,
+            case: HttpErrorCase::ZeroThree,
+            error_log: format!("Email already registered: {}", account.email),
+            output: String::from("Email already registered"),
+        },
+        AppError::SqlxError { msg } => HttpError {
+            status: 500,
+            scenario: HttpScenario::Register,
+            case: HttpErrorCase::ZeroOne,
+            error_log: msg,
+            output: String::from("Internal Server Error"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::Register,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
 
     let register_result = RegisterResult { saved_account };
 
@@ -70,31 +96,213 @@ async fn handle_login_user(
     request_ctx: Extension<RequestContext>,
     SafeJson(payload): SafeJson<LoginRequest>,
 ) -> AppResult<LoginResult> {
-    // TODO query dll
-    let logged_account = login_user(&ctx.db, &payload.email, &payload.password)
+    let login_outcome = login_user(
+        &ctx.db,
+        &ctx.config,
+        &request_ctx.origin(),
+        &payload.email,
+        &payload.password,
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::InvalidCredentials { msg } => HttpError {
+            status: 400,
+            scenario: HttpScenario::Login,
+            case: HttpErrorCase::ZeroFour,
+            error_log: format!("Invalid email/password: {}", msg),
+            output: String::from("Invalid email/password"),
+        },
+        AppError::EmailNotVerified { msg } => HttpError {
+            status: 403,
+            scenario: HttpScenario::Login,
+            case: HttpErrorCase::ZeroSeven,
+            error_log: msg,
+            output: String::from("Email not verified"),
+        },
+        AppError::AccountDisabled { msg } => HttpError {
+            status: 403,
+            scenario: HttpScenario::Login,
+            case: HttpErrorCase::OneOne,
+            error_log: msg,
+            output: String::from("Account disabled"),
+        },
+        AppError::AccountLocked { until } => HttpError {
+            status: 403,
+            scenario: HttpScenario::Login,
+            case: HttpErrorCase::ZeroEight,
+            error_log: format!("Account locked until {}", until.to_rfc3339()),
+            output: String::from("Account temporarily locked"),
+        },
+        AppError::TooManyAttempts { msg } => HttpError {
+            status: 429,
+            scenario: HttpScenario::Login,
+            case: HttpErrorCase::OneTwo,
+            error_log: msg,
+            output: String::from("Too Many Requests"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::Login,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
+
+    Ok(LoginResult::into_api_response(
+        login_outcome,
+        HttpScenario::Login,
+    ))
+}
+
+async fn handle_get_profile(auth: AuthAccount) -> AppResult<ProfileResult> {
+    auth.require_scope(ApiKeyScope::AccountRead, HttpScenario::Profile)?;
+
+    let profile_result = ProfileResult {
+        account_profile: AccountProfile::from(&auth.account),
+    };
+
+    Ok(ApiResponse {
+        response_code: String::from("2001500"),
+        response_message: String::from("Successful"),
+        data: profile_result,
+    })
+}
+
+async fn handle_logout(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    auth: AuthSession,
+) -> AppResult<LogoutResult> {
+    let revoked_sessions = revoke_session(&ctx.db, &request_ctx.origin(), &auth.session)
+        .await
+        .map_err(|err| HttpError {
+            status: 500,
+            scenario: HttpScenario::Logout,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", err),
+            output: String::from("Internal Server error"),
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2001600"),
+        response_message: String::from("Successful"),
+        data: LogoutResult { revoked_sessions },
+    })
+}
+
+async fn handle_logout_all(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    auth: AuthSession,
+) -> AppResult<LogoutResult> {
+    let revoked_sessions = revoke_all_sessions(
+        &ctx.db,
+        &request_ctx.origin(),
+        auth.account.id,
+        "logout everywhere",
+    )
+    .await
+    .map_err(|err| HttpError {
+        status: 500,
+        scenario: HttpScenario::LogoutAll,
+        case: HttpErrorCase::ZeroOne,
+        error_log: format!("Unexpected error: {:?}", err),
+        output: String::from("Internal Server error"),
+    })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2001700"),
+        response_message: String::from("Successful"),
+        data: LogoutResult { revoked_sessions },
+    })
+}
+
+async fn handle_list_sessions(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+) -> AppResult<SessionsResult> {
+    let sessions = list_active_sessions(&ctx.db, auth.account.id, &auth.session.id)
         .await
-        .map_err(|err| match err {
-            AppError::InvalidCredentials { msg } => HttpError {
-                status: 400,
-                scenario: HttpScenario::Login,
-                case: HttpErrorCase::ZeroFour,
-                error_log: format!("Invalid email/ password"),
-                output: format!("Invalid email/password"),
-            },
-            other => HttpError {
-                status: 500,
-                scenario: HttpScenario::Login,
-                case: HttpErrorCase::ZeroOne,
-                error_log: format!("Unexpected error: {:?}", other),
-                output: String::from("Internal Server error"),
-            },
+        .map_err(|err| HttpError {
+            status: 500,
+            scenario: HttpScenario::Sessions,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", err),
+            output: String::from("Internal Server error"),
         })?;
 
-    let login_result = LoginResult { logged_account };
+    Ok(ApiResponse {
+        response_code: String::from("2001800"),
+        response_message: String::from("Successful"),
+        data: SessionsResult { sessions },
+    })
+}
+
+async fn handle_refresh_session(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    SafeJson(payload): SafeJson<RefreshRequest>,
+) -> AppResult<RefreshResult> {
+    let logged_account = refresh_session(
+        &ctx.db,
+        &ctx.config,
+        &request_ctx.origin(),
+        &payload.refresh_token,
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::InvalidToken { msg } => HttpError {
+            status: 401,
+            scenario: HttpScenario::Refresh,
+            case: HttpErrorCase::ZeroTwo,
+            error_log: format!("Invalid refresh token: {}", msg),
+            output: String::from("Invalid Refresh Token"),
+        },
+        AppError::RefreshTokenReused { msg } => HttpError {
+            status: 401,
+            scenario: HttpScenario::Refresh,
+            case: HttpErrorCase::ZeroTwo,
+            error_log: format!("Refresh token reuse: {}", msg),
+            output: String::from("Invalid Refresh Token"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::Refresh,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2001900"),
+        response_message: String::from("Successful"),
+        data: RefreshResult { logged_account },
+    })
+}
+
+async fn handle_list_activity(
+    ctx: Extension<ApiContext>,
+    auth: AuthAccount,
+    Query(query): Query<PageQuery>,
+) -> AppResult<ActivityResult> {
+    auth.require_scope(ApiKeyScope::AccountRead, HttpScenario::AccountActivity)?;
+    let page = query.validate(HttpScenario::AccountActivity)?;
+
+    let activity = list_auth_events(&ctx.db, auth.account.id, page.page, page.page_size)
+        .await
+        .map_err(|err| HttpError {
+            status: 500,
+            scenario: HttpScenario::AccountActivity,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", err),
+            output: String::from("Internal Server error"),
+        })?;
 
     Ok(ApiResponse {
-        response_code: String::from("2001400"),
+        response_code: String::from("2004600"),
         response_message: String::from("Successful"),
-        data: login_result,
+        data: ActivityResult { activity },
     })
 }
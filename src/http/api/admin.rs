@@ -0,0 +1,234 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, Query},
+    routing::{get, post},
+};
+
+use crate::{
+    http::{
+        context::{ApiContext, RequestContext},
+        request::{
+            admin::{AdminAccountsQuery, parse_account_id},
+            page::PageQuery,
+            require_role::AdminSession,
+        },
+        result::{
+            admin::{
+                AdminAccountResult, AdminAccountsResult, AdminActionResult, AdminActivityResult,
+                AdminRevokeResult,
+            },
+            app_result::{ApiResponse, AppResult, HttpError},
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::admin::{
+            disable_account, enable_account, force_logout, get_account, get_account_activity,
+            list_accounts, trigger_password_reset,
+        },
+        utils::error::AppError,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/admin/accounts", get(handle_list_accounts))
+        .route("/admin/accounts/{account_id}", get(handle_get_account))
+        .route(
+            "/admin/accounts/{account_id}/activity",
+            get(handle_get_account_activity),
+        )
+        .route(
+            "/admin/accounts/{account_id}/disable",
+            post(handle_disable_account),
+        )
+        .route(
+            "/admin/accounts/{account_id}/enable",
+            post(handle_enable_account),
+        )
+        .route(
+            "/admin/accounts/{account_id}/logout",
+            post(handle_force_logout),
+        )
+        .route(
+            "/admin/accounts/{account_id}/password-reset",
+            post(handle_trigger_password_reset),
+        )
+}
+
+async fn handle_list_accounts(
+    ctx: Extension<ApiContext>,
+    _admin: AdminSession,
+    Query(query): Query<AdminAccountsQuery>,
+) -> AppResult<AdminAccountsResult> {
+    let filter = query.validate()?;
+
+    let account_page = list_accounts(&ctx.db, &filter.query, filter.page, filter.page_size)
+        .await
+        .map_err(|err| unexpected_error(err, HttpScenario::AdminListAccounts))?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003300"),
+        response_message: String::from("Successful"),
+        data: AdminAccountsResult { account_page },
+    })
+}
+
+async fn handle_get_account(
+    ctx: Extension<ApiContext>,
+    _admin: AdminSession,
+    Path(account_id): Path<String>,
+) -> AppResult<AdminAccountResult> {
+    let account_id = parse_account_id(&account_id, HttpScenario::AdminViewAccount)?;
+
+    let account_detail = get_account(&ctx.db, account_id)
+        .await
+        .map_err(|err| admin_error(err, HttpScenario::AdminViewAccount))?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003400"),
+        response_message: String::from("Successful"),
+        data: AdminAccountResult { account_detail },
+    })
+}
+
+async fn handle_get_account_activity(
+    ctx: Extension<ApiContext>,
+    _admin: AdminSession,
+    Path(account_id): Path<String>,
+    Query(query): Query<PageQuery>,
+) -> AppResult<AdminActivityResult> {
+    let account_id = parse_account_id(&account_id, HttpScenario::AdminAccountActivity)?;
+    let page = query.validate(HttpScenario::AdminAccountActivity)?;
+
+    let activity = get_account_activity(&ctx.db, account_id, page.page, page.page_size)
+        .await
+        .map_err(|err| admin_error(err, HttpScenario::AdminAccountActivity))?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2004700"),
+        response_message: String::from("Successful"),
+        data: AdminActivityResult { activity },
+    })
+}
+
+async fn handle_disable_account(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    admin: AdminSession,
+    Path(account_id): Path<String>,
+) -> AppResult<AdminRevokeResult> {
+    let account_id = parse_account_id(&account_id, HttpScenario::AdminDisableAccount)?;
+
+    let revoked_sessions = disable_account(
+        &ctx.db,
+        &request_ctx.origin(),
+        &admin.auth.account,
+        account_id,
+    )
+    .await
+    .map_err(|err| admin_error(err, HttpScenario::AdminDisableAccount))?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003500"),
+        response_message: String::from("Successful"),
+        data: AdminRevokeResult { revoked_sessions },
+    })
+}
+
+async fn handle_enable_account(
+    ctx: Extension<ApiContext>,
+    admin: AdminSession,
+    Path(account_id): Path<String>,
+) -> AppResult<AdminActionResult> {
+    let account_id = parse_account_id(&account_id, HttpScenario::AdminEnableAccount)?;
+
+    enable_account(&ctx.db, &admin.auth.account, account_id)
+        .await
+        .map_err(|err| admin_error(err, HttpScenario::AdminEnableAccount))?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003600"),
+        response_message: String::from("Successful"),
+        data: AdminActionResult {},
+    })
+}
+
+async fn handle_force_logout(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    admin: AdminSession,
+    Path(account_id): Path<String>,
+) -> AppResult<AdminRevokeResult> {
+    let account_id = parse_account_id(&account_id, HttpScenario::AdminLogoutAccount)?;
+
+    let revoked_sessions = force_logout(
+        &ctx.db,
+        &request_ctx.origin(),
+        &admin.auth.account,
+        account_id,
+    )
+    .await
+    .map_err(|err| admin_error(err, HttpScenario::AdminLogoutAccount))?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003700"),
+        response_message: String::from("Successful"),
+        data: AdminRevokeResult { revoked_sessions },
+    })
+}
+
+async fn handle_trigger_password_reset(
+    ctx: Extension<ApiContext>,
+    admin: AdminSession,
+    Path(account_id): Path<String>,
+) -> AppResult<AdminActionResult> {
+    let account_id = parse_account_id(&account_id, HttpScenario::AdminResetPassword)?;
+
+    trigger_password_reset(
+        &ctx.db,
+        &ctx.config,
+        ctx.mailer.as_ref(),
+        &admin.auth.account,
+        account_id,
+    )
+    .await
+    .map_err(|err| admin_error(err, HttpScenario::AdminResetPassword))?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003800"),
+        response_message: String::from("Successful"),
+        data: AdminActionResult {},
+    })
+}
+
+/// Errors shared by every endpoint acting on one account.
+fn admin_error(err: AppError, scenario: HttpScenario) -> HttpError {
+    match err {
+        AppError::AccountNotFound { msg } => HttpError {
+            status: 404,
+            scenario,
+            case: HttpErrorCase::ZeroOne,
+            error_log: msg,
+            output: String::from("Account not found"),
+        },
+        AppError::AdminAction { msg } => HttpError {
+            status: 400,
+            scenario,
+            case: HttpErrorCase::ZeroNine,
+            error_log: msg,
+            output: String::from("Action not allowed on this account"),
+        },
+        other => unexpected_error(other, scenario),
+    }
+}
+
+fn unexpected_error(err: AppError, scenario: HttpScenario) -> HttpError {
+    HttpError {
+        status: 500,
+        scenario,
+        case: HttpErrorCase::ZeroOne,
+        error_log: format!("Unexpected error: {:?}", err),
+        output: String::from("Internal Server error"),
+    }
+}
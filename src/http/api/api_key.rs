@@ -0,0 +1,129 @@
+use axum::{
+    Extension, Router,
+    extract::Path,
+    routing::{delete, get},
+};
+
+use crate::{
+    http::{
+        context::ApiContext,
+        request::{api_key::CreateApiKeyRequest, auth_session::AuthSession, safe_json::SafeJson},
+        result::{
+            api_key::{ApiKeysResult, CreateApiKeyResult, RevokeApiKeyResult},
+            app_result::{ApiResponse, AppResult, HttpError},
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::api_key::{create_api_key, list_api_keys, revoke_own_api_key},
+        utils::error::AppError,
+    },
+};
+
+/// Keys are managed from a signed-in session only, so a leaked key cannot
+/// mint more keys.
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/account/api-keys",
+            get(handle_list_api_keys).post(handle_create_api_key),
+        )
+        .route("/account/api-keys/{key_id}", delete(handle_revoke_api_key))
+}
+
+async fn handle_create_api_key(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+    SafeJson(payload): SafeJson<CreateApiKeyRequest>,
+) -> AppResult<CreateApiKeyResult> {
+    let api_key = create_api_key(
+        &ctx.db,
+        &auth.account,
+        payload.name.trim(),
+        &payload.parsed_scopes(),
+        payload.expires_in_days,
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::InsufficientRole { msg } => HttpError {
+            status: 403,
+            scenario: HttpScenario::CreateApiKey,
+            case: HttpErrorCase::OneThree,
+            error_log: msg,
+            output: String::from("Forbidden"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::CreateApiKey,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003900"),
+        response_message: String::from("Successful"),
+        data: CreateApiKeyResult { api_key },
+    })
+}
+
+async fn handle_list_api_keys(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+) -> AppResult<ApiKeysResult> {
+    let api_keys = list_api_keys(&ctx.db, auth.account.id)
+        .await
+        .map_err(|err| HttpError {
+            status: 500,
+            scenario: HttpScenario::ListApiKeys,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", err),
+            output: String::from("Internal Server error"),
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2004000"),
+        response_message: String::from("Successful"),
+        data: ApiKeysResult { api_keys },
+    })
+}
+
+async fn handle_revoke_api_key(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+    Path(key_id): Path<String>,
+) -> AppResult<RevokeApiKeyResult> {
+    let key_id = key_id.parse::<i32>().map_err(|_| HttpError {
+        status: 400,
+        scenario: HttpScenario::RevokeApiKey,
+        case: HttpErrorCase::ZeroOne,
+        error_log: format!("API key id {} is not a number", key_id),
+        output: String::from("Invalid Field Format keyId"),
+    })?;
+
+    revoke_own_api_key(&ctx.db, auth.account.id, key_id)
+        .await
+        .map_err(|err| match err {
+            AppError::ApiKeyNotFound { msg } => HttpError {
+                status: 404,
+                scenario: HttpScenario::RevokeApiKey,
+                case: HttpErrorCase::ZeroOne,
+                error_log: msg,
+                output: String::from("API key not found"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::RevokeApiKey,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2004100"),
+        response_message: String::from("Successful"),
+        data: RevokeApiKeyResult {},
+    })
+}
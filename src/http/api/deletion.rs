@@ -0,0 +1,86 @@
+use axum::{
+    Extension, Router,
+    routing::{delete, post},
+};
+
+use crate::{
+    http::{
+        context::ApiContext,
+        request::auth_session::AuthSession,
+        result::{
+            account::{CancelDeletionResult, DeleteAccountResult},
+            app_result::{ApiResponse, AppResult, HttpError},
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::deletion::{cancel_deletion, request_account_deletion},
+        utils::error::AppError,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/account", delete(handle_delete_account))
+        .route("/account/deletion/cancel", post(handle_cancel_deletion))
+}
+
+async fn handle_delete_account(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+) -> AppResult<DeleteAccountResult> {
+    let scheduled_deletion = request_account_deletion(&ctx.db, &ctx.config, &auth.account)
+        .await
+        .map_err(|err| match err {
+            AppError::AccountDeletionState { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::DeleteAccount,
+                case: HttpErrorCase::ZeroNine,
+                error_log: msg,
+                output: String::from("Account deletion already scheduled"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::DeleteAccount,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2002800"),
+        response_message: String::from("Successful"),
+        data: DeleteAccountResult { scheduled_deletion },
+    })
+}
+
+async fn handle_cancel_deletion(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+) -> AppResult<CancelDeletionResult> {
+    cancel_deletion(&ctx.db, auth.account.id)
+        .await
+        .map_err(|err| match err {
+            AppError::AccountDeletionState { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::CancelDeletion,
+                case: HttpErrorCase::ZeroNine,
+                error_log: msg,
+                output: String::from("Account deletion not scheduled"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::CancelDeletion,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2002900"),
+        response_message: String::from("Successful"),
+        data: CancelDeletionResult {},
+    })
+}
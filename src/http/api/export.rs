@@ -0,0 +1,132 @@
+use axum::{
+    Extension, Router,
+    body::Body,
+    extract::Query,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use tokio_util::io::ReaderStream;
+
+use crate::{
+    http::{
+        context::ApiContext,
+        request::{auth_session::AuthSession, export::DownloadExportQuery},
+        result::{
+            app_result::{ApiResponse, AppResult, HttpError},
+            export::DataExportResult,
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::export::{fetch_data_export_status, open_data_export, request_data_export},
+        utils::error::AppError,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/account/export",
+            get(handle_get_data_export).post(handle_request_data_export),
+        )
+        .route("/account/export/download", get(handle_download_data_export))
+}
+
+async fn handle_request_data_export(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+) -> AppResult<DataExportResult> {
+    let data_export = request_data_export(&ctx.db, &ctx.config, &ctx.tasks, auth.account.id)
+        .await
+        .map_err(|err| match err {
+            AppError::DataExportState { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::RequestDataExport,
+                case: HttpErrorCase::ZeroNine,
+                error_log: msg,
+                output: String::from("Data export already in progress"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::RequestDataExport,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003000"),
+        response_message: String::from("Successful"),
+        data: DataExportResult { data_export },
+    })
+}
+
+async fn handle_get_data_export(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+) -> AppResult<DataExportResult> {
+    let data_export = fetch_data_export_status(&ctx.db, &ctx.config, auth.account.id)
+        .await
+        .map_err(|err| match err {
+            AppError::DataExportState { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::DataExportStatus,
+                case: HttpErrorCase::ZeroNine,
+                error_log: msg,
+                output: String::from("No data export requested"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::DataExportStatus,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2003100"),
+        response_message: String::from("Successful"),
+        data: DataExportResult { data_export },
+    })
+}
+
+/// Streams the archive instead of buffering it; the download token in the
+/// link is the only credential.
+async fn handle_download_data_export(
+    ctx: Extension<ApiContext>,
+    Query(query): Query<DownloadExportQuery>,
+) -> Result<Response, HttpError> {
+    let file = open_data_export(&ctx.db, &ctx.config, &query.token)
+        .await
+        .map_err(|err| match err {
+            AppError::InvalidToken { msg } => HttpError {
+                status: 401,
+                scenario: HttpScenario::DownloadDataExport,
+                case: HttpErrorCase::ZeroFive,
+                error_log: format!("Invalid data export token: {}", msg),
+                output: String::from("Invalid or expired link"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::DownloadDataExport,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/json"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"plug-and-plant-export.json\"",
+            ),
+        ],
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response())
+}
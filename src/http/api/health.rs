@@ -0,0 +1,34 @@
+use axum::{Extension, Json, Router, http::StatusCode, routing::get};
+
+use crate::{
+    http::{context::ApiContext, result::health::LivenessResult},
+    services::{
+        handler::health::check_readiness,
+        model::health::{HealthStatus, ReadinessReport},
+    },
+};
+
+/// Probes for the orchestrator. They are merged outside the request context
+/// middleware, so they are neither logged nor given a request id, and they
+/// answer plain status codes instead of the usual response envelope.
+pub fn router() -> Router {
+    Router::new()
+        .route("/health/live", get(handle_liveness))
+        .route("/health/ready", get(handle_readiness))
+}
+
+async fn handle_liveness() -> Json<LivenessResult> {
+    Json(LivenessResult {
+        status: HealthStatus::Up,
+    })
+}
+
+async fn handle_readiness(ctx: Extension<ApiContext>) -> (StatusCode, Json<ReadinessReport>) {
+    let report = check_readiness(&ctx.db, &ctx.config, ctx.shutdown.is_cancelled()).await;
+
+    let status = match report.status {
+        HealthStatus::Up => StatusCode::OK,
+        HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    (status, Json(report))
+}
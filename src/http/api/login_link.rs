@@ -0,0 +1,120 @@
+use axum::{Extension, Router, routing::post};
+use tracing::Instrument;
+
+use crate::{
+    http::{
+        context::{ApiContext, RequestContext},
+        request::{
+            account::{LoginLinkRequest, VerifyLoginLinkRequest},
+            safe_json::SafeJson,
+        },
+        result::{
+            account::{LoginLinkResult, LoginResult},
+            app_result::{ApiResponse, AppResult, HttpError},
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::login_link::{ensure_login_link_enabled, login_with_link, request_login_link},
+        utils::error::AppError,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/account/login/link", post(handle_request_login_link))
+        .route("/account/login/link/verify", post(handle_login_with_link))
+}
+
+async fn handle_request_login_link(
+    ctx: Extension<ApiContext>,
+    SafeJson(payload): SafeJson<LoginLinkRequest>,
+) -> AppResult<LoginLinkResult> {
+    ensure_login_link_enabled(&ctx.config).map_err(|err| HttpError {
+        status: 404,
+        scenario: HttpScenario::RequestLoginLink,
+        case: HttpErrorCase::ZeroOne,
+        error_log: format!("{:?}", err),
+        output: String::from("Not Found"),
+    })?;
+
+    // Same as the forgot password flow: nothing about the account may leak
+    // through the response or its timing.
+    let ctx = ctx.0;
+    let tasks = ctx.tasks.clone();
+    tasks.spawn(
+        async move {
+            if let Err(err) =
+                request_login_link(&ctx.db, &ctx.config, ctx.mailer.as_ref(), &payload.email).await
+            {
+                tracing::error!("Failed to process sign-in link request: {:?}", err);
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+
+    Ok(ApiResponse {
+        response_code: String::from("2004400"),
+        response_message: String::from("Successful"),
+        data: LoginLinkResult {},
+    })
+}
+
+async fn handle_login_with_link(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    SafeJson(payload): SafeJson<VerifyLoginLinkRequest>,
+) -> AppResult<LoginResult> {
+    let login_outcome =
+        login_with_link(&ctx.db, &ctx.config, &request_ctx.origin(), &payload.token)
+            .await
+            .map_err(|err| match err {
+                AppError::LoginLinkDisabled { msg } => HttpError {
+                    status: 404,
+                    scenario: HttpScenario::LoginWithLink,
+                    case: HttpErrorCase::ZeroOne,
+                    error_log: msg,
+                    output: String::from("Not Found"),
+                },
+                AppError::InvalidToken { msg } => HttpError {
+                    status: 400,
+                    scenario: HttpScenario::LoginWithLink,
+                    case: HttpErrorCase::ZeroFive,
+                    error_log: format!("Invalid sign-in link: {}", msg),
+                    output: String::from("Invalid or expired token"),
+                },
+                AppError::AccountDisabled { msg } => HttpError {
+                    status: 403,
+                    scenario: HttpScenario::LoginWithLink,
+                    case: HttpErrorCase::OneOne,
+                    error_log: msg,
+                    output: String::from("Account disabled"),
+                },
+                AppError::AccountLocked { until } => HttpError {
+                    status: 403,
+                    scenario: HttpScenario::LoginWithLink,
+                    case: HttpErrorCase::ZeroEight,
+                    error_log: format!("Account locked until {}", until.to_rfc3339()),
+                    output: String::from("Account temporarily locked"),
+                },
+                AppError::TooManyAttempts { msg } => HttpError {
+                    status: 429,
+                    scenario: HttpScenario::LoginWithLink,
+                    case: HttpErrorCase::OneTwo,
+                    error_log: msg,
+                    output: String::from("Too Many Requests"),
+                },
+                other => HttpError {
+                    status: 500,
+                    scenario: HttpScenario::LoginWithLink,
+                    case: HttpErrorCase::ZeroOne,
+                    error_log: format!("Unexpected error: {:?}", other),
+                    output: String::from("Internal Server error"),
+                },
+            })?;
+
+    Ok(LoginResult::into_api_response(
+        login_outcome,
+        HttpScenario::LoginWithLink,
+    ))
+}
@@ -0,0 +1,50 @@
+use axum::{
+    Extension, Router,
+    http::{HeaderMap, StatusCode, header::CONTENT_TYPE},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+
+use crate::{
+    http::context::ApiContext,
+    services::{
+        metrics::{METRICS, PoolStats},
+        utils::token::constant_time_eq,
+    },
+};
+
+/// Prometheus scrape endpoint. Like the health probes it sits outside the
+/// request context middleware, so scrapes are neither logged nor counted.
+/// It answers only scrapes carrying the configured metrics token.
+pub fn router() -> Router {
+    Router::new().route("/metrics", get(handle_metrics))
+}
+
+async fn handle_metrics(ctx: Extension<ApiContext>, headers: HeaderMap) -> Response {
+    let metrics_token = ctx.config.metrics_token.as_deref().unwrap_or_default();
+    if metrics_token.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let bearer = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default();
+    if !constant_time_eq(bearer.as_bytes(), metrics_token.as_bytes()) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let pool_stats = PoolStats {
+        size: ctx.db.size(),
+        idle: ctx.db.num_idle(),
+        max_size: ctx.db.options().get_max_connections(),
+    };
+
+    (
+        [(CONTENT_TYPE, "text/plain; version=0.0.4")],
+        METRICS.render(&pool_stats),
+    )
+        .into_response()
+}
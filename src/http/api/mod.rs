@@ -1 +1,12 @@
 pub mod account;
+pub mod admin;
+pub mod api_key;
+pub mod deletion;
+pub mod export;
+pub mod health;
+pub mod login_link;
+pub mod metrics;
+pub mod oidc;
+pub mod password;
+pub mod totp;
+pub mod verification;
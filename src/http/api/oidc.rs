@@ -0,0 +1,201 @@
+use axum::{
+    Extension, Router,
+    extract::{Path, Query},
+    http::{HeaderMap, HeaderName, header::SET_COOKIE},
+    response::AppendHeaders,
+    routing::get,
+};
+
+use crate::{
+    config::Config,
+    http::{
+        context::{ApiContext, RequestContext},
+        request::oidc::OidcCallbackQuery,
+        result::{
+            account::LoginResult,
+            app_result::{ApiResponse, AppResult, HttpError},
+            oidc::OidcAuthorizeResult,
+        },
+        utils::{cookie::read_cookie, error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::oidc::{complete_oidc_login, start_oidc_login},
+        utils::error::AppError,
+    },
+};
+
+/// Binds a started login to the browser that started it.
+const OIDC_STATE_COOKIE_NAME: &str = "oidc_state";
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/account/oidc/{provider}/authorize",
+            get(handle_oidc_authorize),
+        )
+        .route(
+            "/account/oidc/{provider}/callback",
+            get(handle_oidc_callback),
+        )
+}
+
+async fn handle_oidc_authorize(
+    ctx: Extension<ApiContext>,
+    Path(provider): Path<String>,
+) -> Result<
+    (
+        AppendHeaders<[(HeaderName, String); 1]>,
+        ApiResponse<OidcAuthorizeResult>,
+    ),
+    HttpError,
+> {
+    let oidc_authorization = start_oidc_login(&ctx.db, &ctx.config, &ctx.oidc, &provider)
+        .await
+        .map_err(|err| match err {
+            AppError::OidcProviderNotFound { msg } => HttpError {
+                status: 404,
+                scenario: HttpScenario::OidcAuthorize,
+                case: HttpErrorCase::ZeroOne,
+                error_log: msg,
+                output: String::from("OIDC provider not found"),
+            },
+            AppError::OidcProvider { msg } => HttpError {
+                status: 502,
+                scenario: HttpScenario::OidcAuthorize,
+                case: HttpErrorCase::ZeroOne,
+                error_log: msg,
+                output: String::from("Identity provider unavailable"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::OidcAuthorize,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    let state_cookie = state_cookie(
+        &ctx.config,
+        &oidc_authorization.state,
+        ctx.config.oidc_state_ttl_seconds,
+    );
+    Ok((
+        AppendHeaders([(SET_COOKIE, state_cookie)]),
+        ApiResponse {
+            response_code: String::from("2004200"),
+            response_message: String::from("Successful"),
+            data: OidcAuthorizeResult { oidc_authorization },
+        },
+    ))
+}
+
+/// The provider sends the browser back with a top-level GET, which is what
+/// `SameSite=Lax` still lets the cookie ride along with.
+fn state_cookie(config: &Config, state: &str, max_age: i64) -> String {
+    let secure = if config.public_base_url.starts_with("https://") {
+        "; Secure"
+    } else {
+        ""
+    };
+    format!(
+        "{}={}; Max-Age={}; Path=/; HttpOnly; SameSite=Lax{}",
+        OIDC_STATE_COOKIE_NAME, state, max_age, secure
+    )
+}
+
+async fn handle_oidc_callback(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<OidcCallbackQuery>,
+) -> (
+    AppendHeaders<[(HeaderName, String); 1]>,
+    AppResult<LoginResult>,
+) {
+    // The state is spent whatever the outcome, so every callback response
+    // drops the cookie.
+    let result = complete_oidc_callback(&ctx, &request_ctx, &provider, &headers, query).await;
+    (
+        AppendHeaders([(SET_COOKIE, state_cookie(&ctx.config, "", 0))]),
+        result,
+    )
+}
+
+async fn complete_oidc_callback(
+    ctx: &ApiContext,
+    request_ctx: &RequestContext,
+    provider: &str,
+    headers: &HeaderMap,
+    query: OidcCallbackQuery,
+) -> AppResult<LoginResult> {
+    let browser_state = read_cookie(headers, OIDC_STATE_COOKIE_NAME);
+    let callback = query.validate(browser_state.as_deref())?;
+
+    let login_outcome = complete_oidc_login(
+        &ctx.db,
+        &ctx.config,
+        &ctx.oidc,
+        provider,
+        &request_ctx.origin(),
+        &callback,
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::OidcProviderNotFound { msg } => HttpError {
+            status: 404,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::ZeroOne,
+            error_log: msg,
+            output: String::from("OIDC provider not found"),
+        },
+        AppError::OidcProvider { msg } => HttpError {
+            status: 502,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::ZeroOne,
+            error_log: msg,
+            output: String::from("Identity provider unavailable"),
+        },
+        AppError::InvalidOidcLogin { msg } => HttpError {
+            status: 400,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::ZeroFive,
+            error_log: msg,
+            output: String::from("OIDC login failed"),
+        },
+        AppError::EmailNotVerified { msg } => HttpError {
+            status: 403,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::ZeroSeven,
+            error_log: msg,
+            output: String::from("Email not verified"),
+        },
+        AppError::AccountNotFound { msg } => HttpError {
+            status: 403,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::ZeroFour,
+            error_log: msg,
+            output: String::from("No account for this identity"),
+        },
+        AppError::AccountDisabled { msg } => HttpError {
+            status: 403,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::OneOne,
+            error_log: msg,
+            output: String::from("Account disabled"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
+
+    Ok(LoginResult::into_api_response(
+        login_outcome,
+        HttpScenario::OidcCallback,
+    ))
+}
@@ -0,0 +1,147 @@
+use axum::{Extension, Router, routing::post};
+use tracing::Instrument;
+
+use crate::{
+    http::{
+        context::{ApiContext, RequestContext},
+        request::{
+            account::{ChangePasswordRequest, ForgotPasswordRequest, ResetPasswordRequest},
+            auth_session::AuthSession,
+            safe_json::SafeJson,
+        },
+        result::{
+            app_result::{ApiResponse, AppResult, HttpError},
+            password::{ChangePasswordResult, ForgotPasswordResult, ResetPasswordResult},
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::password::{change_password, request_password_reset, reset_password},
+        utils::error::AppError,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/account/password/forgot", post(handle_forgot_password))
+        .route("/account/password/reset", post(handle_reset_password))
+        .route("/account/password", post(handle_change_password))
+}
+
+async fn handle_forgot_password(
+    ctx: Extension<ApiContext>,
+    SafeJson(payload): SafeJson<ForgotPasswordRequest>,
+) -> AppResult<ForgotPasswordResult> {
+    // Lookup and delivery happen off the request path so neither the response
+    // body nor its timing reveals whether the email is registered.
+    let ctx = ctx.0;
+    let tasks = ctx.tasks.clone();
+    tasks.spawn(
+        async move {
+            if let Err(err) =
+                request_password_reset(&ctx.db, &ctx.config, ctx.mailer.as_ref(), &payload.email)
+                    .await
+            {
+                tracing::error!("Failed to process password reset request: {:?}", err);
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+
+    Ok(ApiResponse {
+        response_code: String::from("2002000"),
+        response_message: String::from("Successful"),
+        data: ForgotPasswordResult {},
+    })
+}
+
+async fn handle_reset_password(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    SafeJson(payload): SafeJson<ResetPasswordRequest>,
+) -> AppResult<ResetPasswordResult> {
+    let revoked_sessions = reset_password(
+        &ctx.db,
+        &ctx.config,
+        &request_ctx.origin(),
+        &payload.token,
+        &payload.new_password,
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::InvalidToken { msg } => HttpError {
+            status: 400,
+            scenario: HttpScenario::ResetPassword,
+            case: HttpErrorCase::ZeroFive,
+            error_log: format!("Invalid password reset token: {}", msg),
+            output: String::from("Invalid or expired token"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::ResetPassword,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2002100"),
+        response_message: String::from("Successful"),
+        data: ResetPasswordResult { revoked_sessions },
+    })
+}
+
+async fn handle_change_password(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    auth: AuthSession,
+    SafeJson(payload): SafeJson<ChangePasswordRequest>,
+) -> AppResult<ChangePasswordResult> {
+    let revoked_sessions = change_password(
+        &ctx.db,
+        &ctx.config,
+        &auth.account,
+        &auth.session.id,
+        &payload.current_password,
+        &payload.new_password,
+        &request_ctx.origin(),
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::InvalidCredentials { msg } => HttpError {
+            status: 400,
+            scenario: HttpScenario::ChangePassword,
+            case: HttpErrorCase::ZeroFour,
+            error_log: msg,
+            output: String::from("Invalid current password"),
+        },
+        AppError::PasswordReused { msg } => HttpError {
+            status: 400,
+            scenario: HttpScenario::ChangePassword,
+            case: HttpErrorCase::ZeroNine,
+            error_log: msg,
+            output: String::from("Password was used recently"),
+        },
+        AppError::AccountLocked { until } => HttpError {
+            status: 403,
+            scenario: HttpScenario::ChangePassword,
+            case: HttpErrorCase::ZeroEight,
+            error_log: format!("Account locked until {}", until.to_rfc3339()),
+            output: String::from("Account temporarily locked"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::ChangePassword,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2002700"),
+        response_message: String::from("Successful"),
+        data: ChangePasswordResult { revoked_sessions },
+    })
+}
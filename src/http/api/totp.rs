@@ -0,0 +1,163 @@
+use axum::{Extension, Router, routing::post};
+
+use crate::{
+    http::{
+        context::{ApiContext, RequestContext},
+        request::{
+            auth_session::AuthSession,
+            safe_json::SafeJson,
+            totp::{TotpConfirmRequest, TotpLoginRequest},
+        },
+        result::{
+            account::LoginResult,
+            app_result::{ApiResponse, AppResult, HttpError},
+            totp::{TotpConfirmResult, TotpSetupResult},
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::totp::{complete_totp_login, confirm_totp, setup_totp},
+        model::account::LoginOutcome,
+        utils::error::AppError,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/account/2fa/totp/setup", post(handle_totp_setup))
+        .route("/account/2fa/totp/confirm", post(handle_totp_confirm))
+        .route("/account/login/totp", post(handle_totp_login))
+}
+
+async fn handle_totp_setup(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+) -> AppResult<TotpSetupResult> {
+    let totp_setup = setup_totp(&ctx.db, &ctx.config, &auth.account)
+        .await
+        .map_err(|err| match err {
+            AppError::TotpState { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::TotpSetup,
+                case: HttpErrorCase::ZeroNine,
+                error_log: msg,
+                output: String::from("TOTP already enabled"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::TotpSetup,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2002400"),
+        response_message: String::from("Successful"),
+        data: TotpSetupResult { totp_setup },
+    })
+}
+
+async fn handle_totp_confirm(
+    ctx: Extension<ApiContext>,
+    auth: AuthSession,
+    SafeJson(payload): SafeJson<TotpConfirmRequest>,
+) -> AppResult<TotpConfirmResult> {
+    let recovery_codes = confirm_totp(&ctx.db, &ctx.config, auth.account.id, &payload.code)
+        .await
+        .map_err(|err| match err {
+            AppError::TotpState { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::TotpConfirm,
+                case: HttpErrorCase::ZeroNine,
+                error_log: msg,
+                output: String::from("TOTP setup not pending"),
+            },
+            AppError::InvalidTotpCode { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::TotpConfirm,
+                case: HttpErrorCase::ZeroFour,
+                error_log: msg,
+                output: String::from("Invalid TOTP code"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::TotpConfirm,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2002500"),
+        response_message: String::from("Successful"),
+        data: TotpConfirmResult { recovery_codes },
+    })
+}
+
+async fn handle_totp_login(
+    ctx: Extension<ApiContext>,
+    request_ctx: Extension<RequestContext>,
+    SafeJson(payload): SafeJson<TotpLoginRequest>,
+) -> AppResult<LoginResult> {
+    let logged_account = complete_totp_login(
+        &ctx.db,
+        &ctx.config,
+        &payload.challenge_token,
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+        &request_ctx.origin(),
+    )
+    .await
+    .map_err(|err| match err {
+        AppError::InvalidToken { msg } => HttpError {
+            status: 401,
+            scenario: HttpScenario::LoginTotp,
+            case: HttpErrorCase::ZeroTwo,
+            error_log: format!("Invalid login challenge: {}", msg),
+            output: String::from("Invalid Challenge Token"),
+        },
+        AppError::InvalidTotpCode { msg } => HttpError {
+            status: 400,
+            scenario: HttpScenario::LoginTotp,
+            case: HttpErrorCase::ZeroFour,
+            error_log: msg,
+            output: String::from("Invalid TOTP code"),
+        },
+        AppError::AccountDisabled { msg } => HttpError {
+            status: 403,
+            scenario: HttpScenario::LoginTotp,
+            case: HttpErrorCase::OneOne,
+            error_log: msg,
+            output: String::from("Account disabled"),
+        },
+        AppError::AccountLocked { until } => HttpError {
+            status: 403,
+            scenario: HttpScenario::LoginTotp,
+            case: HttpErrorCase::ZeroEight,
+            error_log: format!("Account locked until {}", until.to_rfc3339()),
+            output: String::from("Account temporarily locked"),
+        },
+        AppError::TooManyAttempts { msg } => HttpError {
+            status: 429,
+            scenario: HttpScenario::LoginTotp,
+            case: HttpErrorCase::OneTwo,
+            error_log: msg,
+            output: String::from("Too Many Requests"),
+        },
+        other => HttpError {
+            status: 500,
+            scenario: HttpScenario::LoginTotp,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Unexpected error: {:?}", other),
+            output: String::from("Internal Server error"),
+        },
+    })?;
+
+    Ok(LoginResult::into_api_response(
+        LoginOutcome::LoggedIn(logged_account),
+        HttpScenario::LoginTotp,
+    ))
+}
@@ -0,0 +1,84 @@
+use axum::{Extension, Router, routing::post};
+use tracing::Instrument;
+
+use crate::{
+    http::{
+        context::ApiContext,
+        request::{
+            account::{ResendVerificationRequest, VerifyEmailRequest},
+            safe_json::SafeJson,
+        },
+        result::{
+            account::{ResendVerificationResult, VerifyEmailResult},
+            app_result::{ApiResponse, AppResult, HttpError},
+        },
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::verification::{resend_verification_mail, verify_email},
+        utils::error::AppError,
+    },
+};
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/account/verify", post(handle_verify_email))
+        .route("/account/verify/resend", post(handle_resend_verification))
+}
+
+async fn handle_verify_email(
+    ctx: Extension<ApiContext>,
+    SafeJson(payload): SafeJson<VerifyEmailRequest>,
+) -> AppResult<VerifyEmailResult> {
+    verify_email(&ctx.db, &payload.token)
+        .await
+        .map_err(|err| match err {
+            AppError::InvalidToken { msg } => HttpError {
+                status: 400,
+                scenario: HttpScenario::VerifyEmail,
+                case: HttpErrorCase::ZeroFive,
+                error_log: format!("Invalid email verification token: {}", msg),
+                output: String::from("Invalid or expired token"),
+            },
+            other => HttpError {
+                status: 500,
+                scenario: HttpScenario::VerifyEmail,
+                case: HttpErrorCase::ZeroOne,
+                error_log: format!("Unexpected error: {:?}", other),
+                output: String::from("Internal Server error"),
+            },
+        })?;
+
+    Ok(ApiResponse {
+        response_code: String::from("2002200"),
+        response_message: String::from("Successful"),
+        data: VerifyEmailResult {},
+    })
+}
+
+async fn handle_resend_verification(
+    ctx: Extension<ApiContext>,
+    SafeJson(payload): SafeJson<ResendVerificationRequest>,
+) -> AppResult<ResendVerificationResult> {
+    // Same as the forgot-password flow: answer before doing the lookup so the
+    // response does not reveal whether the email is registered.
+    let ctx = ctx.0;
+    let tasks = ctx.tasks.clone();
+    tasks.spawn(
+        async move {
+            if let Err(err) =
+                resend_verification_mail(&ctx.db, &ctx.config, ctx.mailer.as_ref(), &payload.email)
+                    .await
+            {
+                tracing::error!("Failed to resend verification mail: {:?}", err);
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+
+    Ok(ApiResponse {
+        response_code: String::from("2002300"),
+        response_message: String::from("Successful"),
+        data: ResendVerificationResult {},
+    })
+}
@@ -1,22 +1,37 @@
 use sqlx::PgPool;
 use std::{collections::HashMap, sync::Arc};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
-use crate::config::Config;
+use crate::{
+    config::Config,
+    services::{mail::MailSender, model::auth_event::ClientOrigin, oidc::OidcClient},
+};
 
 #[derive(Clone, Debug)]
+#[allow(dead_code)]
 pub struct RequestContext {
     pub request_id: String,
     pub path: String,
     pub method: String,
+    pub client_ip: String,
+    pub user_agent: Option<String>,
     pub metadata: HashMap<String, String>,
 }
 
 impl RequestContext {
-    pub fn new(method: String, path: String, request_id: String) -> Self {
+    pub fn new(
+        method: String,
+        path: String,
+        request_id: String,
+        client_ip: String,
+        user_agent: Option<String>,
+    ) -> Self {
         Self {
             request_id,
             path,
             method,
+            client_ip,
+            user_agent,
             metadata: HashMap::new(),
         }
     }
@@ -25,10 +40,26 @@ impl RequestContext {
         self.metadata.insert(key, value);
         self
     }
+
+    /// The part of the request recorded with audit events.
+    pub fn origin(&self) -> ClientOrigin {
+        ClientOrigin {
+            client_ip: self.client_ip.clone(),
+            user_agent: self.user_agent.clone(),
+            request_id: self.request_id.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct ApiContext {
     pub config: Arc<Config>,
     pub db: PgPool,
+    pub mailer: Arc<dyn MailSender>,
+    pub oidc: Arc<OidcClient>,
+    /// Cancelled once the server starts shutting down.
+    pub shutdown: CancellationToken,
+    /// Work a request leaves running after its response, e.g. sending mail.
+    /// The shutdown drain waits for it.
+    pub tasks: TaskTracker,
 }
@@ -1,29 +1,44 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, net::SocketAddr};
 
 use axum::{
     body::Body,
-    extract::Request,
-    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    extract::{ConnectInfo, MatchedPath, Request},
+    http::{
+        HeaderMap, HeaderName, HeaderValue, StatusCode, Version,
+        header::{CONNECTION, CONTENT_DISPOSITION},
+    },
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use bytes::Bytes;
 use chrono::Utc;
 use http_body_util::BodyExt;
+use opentelemetry::global;
+use opentelemetry_http::HeaderExtractor;
 use serde_json::Value;
 use tokio::time::Instant;
 use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use uuid::Uuid;
 
-use crate::http::{
-    context::RequestContext,
-    result::app_result::HttpError,
-    utils::{error::HttpErrorCase, scenario::HttpScenario},
+use crate::{
+    http::{
+        context::{ApiContext, RequestContext},
+        result::app_result::HttpError,
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::metrics::METRICS,
 };
 
 pub async fn request_context_middleware(req: Request, next: Next) -> Response {
     let start_time = Instant::now();
-    process_request_with_context(req, next, start_time)
+    let shutdown = req
+        .extensions()
+        .get::<ApiContext>()
+        .map(|ctx| ctx.shutdown.clone());
+    let keep_alive_version = req.version() <= Version::HTTP_11;
+
+    let mut response = process_request_with_context(req, next, start_time)
         .await
         .unwrap_or_else(|error| {
             tracing::error!("Request processing failed: {}", error);
@@ -35,7 +50,17 @@ pub async fn request_context_middleware(req: Request, next: Next) -> Response {
                 output: "Internal Server Error".to_string(),
             }
             .into_response()
-        })
+        });
+
+    // While draining, keep-alive clients are told to reconnect, which lands
+    // them on a replica that still takes traffic.
+    if keep_alive_version && shutdown.is_some_and(|shutdown| shutdown.is_cancelled()) {
+        response
+            .headers_mut()
+            .insert(CONNECTION, HeaderValue::from_static("close"));
+    }
+
+    response
 }
 
 async fn process_request_with_context(
@@ -45,6 +70,10 @@ async fn process_request_with_context(
 ) -> Result<Response, Box<dyn std::error::Error + Send + Sync>> {
     let method = req.method().to_string();
     let path = req.uri().path().to_string();
+    let route = req.extensions().get::<MatchedPath>().map_or_else(
+        || String::from("unmatched"),
+        |route| route.as_str().to_string(),
+    );
 
     let request_headers = headers_to_map(req.headers());
     let request_body = extract_body_bytes(req.body_mut()).await?;
@@ -54,12 +83,27 @@ async fn process_request_with_context(
         None => &Uuid::new_v4().to_string().replace("-", ""),
     };
 
-    let context = create_request_context(&method, &path, request_id.clone());
+    let client_ip = resolve_client_ip(&req, &request_headers);
+    let user_agent = request_headers.get("user-agent").cloned();
+    let context = create_request_context(&method, &path, request_id.clone(), client_ip, user_agent);
     let request_id = context.request_id.clone();
 
     let new_req = rebuild_request_with_context(req, request_body, context)?;
 
-    let span = tracing::info_span!("http_request", request_id = %request_id);
+    // Each request is the root server span of its trace, continuing the
+    // caller's trace when a `traceparent` header is present.
+    let span = tracing::info_span!(
+        parent: None,
+        "http_request",
+        otel.name = %format!("{} {}", method, route),
+        otel.kind = "server",
+        otel.status_code = tracing::field::Empty,
+        request_id = %request_id,
+        http.response.status_code = tracing::field::Empty,
+    );
+    span.set_parent(global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(new_req.headers()))
+    }));
 
     // Execute request within the span
     let response = async move {
@@ -68,7 +112,7 @@ async fn process_request_with_context(
         let response = next.run(new_req).await;
         let duration = start_time.elapsed();
 
-        process_response(response, &method, &path, duration, request_id).await
+        process_response(response, &method, &path, &route, duration, request_id).await
     }
     .instrument(span)
     .await?;
@@ -88,24 +132,112 @@ async fn extract_body_bytes(
     }
 }
 
+/// JSON fields holding passwords, tokens, keys or second factors. Their
+/// values are masked wherever they appear in a logged body.
+const REDACTED_FIELDS: [&str; 14] = [
+    "password",
+    "currentPassword",
+    "newPassword",
+    "token",
+    "key",
+    "sessionId",
+    "refreshToken",
+    "challengeToken",
+    "secret",
+    "otpauthUri",
+    "code",
+    "recoveryCode",
+    "recoveryCodes",
+    "downloadUrl",
+];
+
+/// Headers carrying session ids, API keys or cookies.
+const REDACTED_HEADERS: [&str; 3] = ["authorization", "cookie", "set-cookie"];
+
+const REDACTED: &str = "[REDACTED]";
+
 fn format_body_for_logging(body_bytes: &Bytes) -> String {
     if body_bytes.is_empty() {
         return String::new();
     }
 
-    if let Ok(json) = serde_json::from_slice::<Value>(body_bytes) {
+    if let Ok(mut json) = serde_json::from_slice::<Value>(body_bytes) {
+        redact_json(&mut json);
         return json.to_string();
     }
 
-    match std::str::from_utf8(body_bytes) {
-        Ok(text) => text.to_string(),
-        Err(_) => "<binary or non-UTF8 content>".to_string(),
+    // Anything but JSON could hold credentials in a shape we cannot mask.
+    format!("<non-JSON content, {} bytes>", body_bytes.len())
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (name, field) in fields.iter_mut() {
+                if REDACTED_FIELDS.contains(&name.as_str()) {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_json(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => {}
     }
 }
 
-fn create_request_context(method: &str, path: &str, request_id: String) -> RequestContext {
-    RequestContext::new(method.to_string(), path.to_string(), request_id)
-        .add_metadata("timestamp".to_string(), Utc::now().to_rfc3339())
+fn redact_headers(headers: &HashMap<String, String>) -> HashMap<&str, &str> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            if REDACTED_HEADERS.contains(&name.as_str()) {
+                (name.as_str(), REDACTED)
+            } else {
+                (name.as_str(), value.as_str())
+            }
+        })
+        .collect()
+}
+
+fn create_request_context(
+    method: &str,
+    path: &str,
+    request_id: String,
+    client_ip: String,
+    user_agent: Option<String>,
+) -> RequestContext {
+    RequestContext::new(
+        method.to_string(),
+        path.to_string(),
+        request_id,
+        client_ip,
+        user_agent,
+    )
+    .add_metadata("timestamp".to_string(), Utc::now().to_rfc3339())
+}
+
+/// Uses the first `X-Forwarded-For` hop when the deployment says a proxy sets
+/// it, otherwise the peer address of the connection.
+fn resolve_client_ip(req: &Request, headers: &HashMap<String, String>) -> String {
+    let trust_forwarded_for = req
+        .extensions()
+        .get::<ApiContext>()
+        .is_some_and(|ctx| ctx.config.trust_forwarded_for);
+
+    if trust_forwarded_for
+        && let Some(forwarded) = headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.split(',').next())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    {
+        return forwarded.to_string();
+    }
+
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| String::from("unknown"))
 }
 
 fn rebuild_request_with_context(
@@ -126,7 +258,7 @@ fn log_incoming_request(
     headers: &HashMap<String, String>,
     body_log: &str,
 ) {
-    let headers_json = serde_json::to_string(headers).unwrap_or_default();
+    let headers_json = serde_json::to_string(&redact_headers(headers)).unwrap_or_default();
     tracing::debug!("[IN]({},{}){},{}", method, path, headers_json, body_log);
 }
 
@@ -134,14 +266,35 @@ async fn process_response(
     response: Response,
     method: &str,
     path: &str,
+    route: &str,
     duration: std::time::Duration,
     request_id: String,
 ) -> Result<Response, Box<dyn std::error::Error + Send + Sync>> {
     let status = response.status();
     let (mut parts, body) = response.into_parts();
 
+    // `HttpError` logs every failure at error level, which would mark the
+    // span failed; only a 5xx is a server span error.
+    let span_status = if status.is_server_error() {
+        "error"
+    } else {
+        "unset"
+    };
+    let span = tracing::Span::current();
+    span.record("http.response.status_code", status.as_u16());
+    span.record("otel.status_code", span_status);
+
     add_timestamp_header(&mut parts.headers, request_id)?;
 
+    // Attachments are streamed through untouched: buffering would defeat the
+    // streaming and logging would copy personal data into the logs.
+    if is_attachment(&parts.headers) {
+        log_outgoing_response(method, path, &parts.headers, &Bytes::new());
+        log_request_summary(method, path, duration, status);
+        METRICS.observe_http_request(method, route, status.as_u16(), "", duration);
+        return Ok(Response::from_parts(parts, body));
+    }
+
     // Extract and log response body
     let response_body_bytes = match body.collect().await {
         Ok(collected) => collected.to_bytes(),
@@ -153,10 +306,33 @@ async fn process_response(
 
     log_outgoing_response(method, path, &parts.headers, &response_body_bytes);
     log_request_summary(method, path, duration, status);
+    METRICS.observe_http_request(
+        method,
+        route,
+        status.as_u16(),
+        &extract_response_code(&response_body_bytes),
+        duration,
+    );
 
     Ok(Response::from_parts(parts, Body::from(response_body_bytes)))
 }
 
+/// The `responseCode` of an `ApiResponse` or `HttpError` body, empty for
+/// anything else.
+fn extract_response_code(body_bytes: &Bytes) -> String {
+    serde_json::from_slice::<Value>(body_bytes)
+        .ok()
+        .and_then(|body| body.get("responseCode")?.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn is_attachment(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_DISPOSITION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("attachment"))
+}
+
 fn add_timestamp_header(
     headers: &mut HeaderMap,
     request_id: String,
@@ -177,7 +353,8 @@ fn add_timestamp_header(
 
 fn log_outgoing_response(method: &str, path: &str, headers: &HeaderMap, body_bytes: &Bytes) {
     let response_headers = headers_to_map(headers);
-    let response_headers_json = serde_json::to_string(&response_headers).unwrap_or_default();
+    let response_headers_json =
+        serde_json::to_string(&redact_headers(&response_headers)).unwrap_or_default();
     let response_body_log = format_body_for_logging(body_bytes);
 
     tracing::debug!(
@@ -215,3 +392,84 @@ fn headers_to_map(headers: &HeaderMap) -> HashMap<String, String> {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, routing::get};
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use tower::Service;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::{dal::query_span, services::telemetry::build_tracer_provider};
+
+    #[test]
+    fn masks_credentials_in_logged_bodies() {
+        let body = Bytes::from_static(
+            br#"{"responseCode":"2003900","apiKey":{"key":"pnp_abc","name":"ci"},"recoveryCodes":["a","b"]}"#,
+        );
+        let logged = format_body_for_logging(&body);
+
+        assert!(!logged.contains("pnp_abc"));
+        assert!(!logged.contains(r#""a""#));
+        assert!(logged.contains(r#""name":"ci""#));
+        assert!(logged.contains(r#""responseCode":"2003900""#));
+    }
+
+    #[test]
+    fn masks_credential_headers() {
+        let headers = HashMap::from([
+            (String::from("authorization"), String::from("Bearer abc")),
+            (String::from("user-agent"), String::from("curl")),
+        ]);
+        let logged = redact_headers(&headers);
+
+        assert_eq!(logged["authorization"], REDACTED);
+        assert_eq!(logged["user-agent"], "curl");
+    }
+
+    #[tokio::test]
+    async fn query_spans_are_children_of_the_request_span() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = build_tracer_provider(exporter.clone(), "test", 1.0);
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut app = Router::new()
+            .route(
+                "/probe/{id}",
+                get(|| async {
+                    async {}.instrument(query_span("SELECT 1;")).await;
+                    "ok"
+                }),
+            )
+            .layer(axum::middleware::from_fn(request_context_middleware));
+        let response = app
+            .call(Request::get("/probe/7").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        provider.force_flush().unwrap();
+        let spans = exporter.get_finished_spans().unwrap();
+        let request = spans
+            .iter()
+            .find(|span| span.name == "GET /probe/{id}")
+            .expect("request span");
+        let query = spans
+            .iter()
+            .find(|span| span.name == "SELECT")
+            .expect("query span");
+
+        assert_eq!(query.parent_span_id, request.span_context.span_id());
+        assert_eq!(
+            query.span_context.trace_id(),
+            request.span_context.trace_id()
+        );
+        assert!(query.attributes.iter().any(|attribute| {
+            attribute.key.as_str() == "db.statement" && attribute.value.as_str() == "SELECT 1;"
+        }));
+    }
+}
@@ -1,13 +1,32 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, os::unix::fs::FileTypeExt, path::Path, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use axum::{Extension, Router};
+use axum_server::Handle;
+use futures::future::BoxFuture;
 use sqlx::PgPool;
-use tokio::{net::TcpListener, time::Instant};
+use tokio::{
+    net::{TcpListener, UnixListener},
+    task::JoinHandle,
+    time::Instant,
+};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 use crate::{
     config::Config,
-    http::{context::ApiContext, middleware::request_context_middleware},
+    http::{
+        context::ApiContext,
+        middleware::request_context_middleware,
+        shutdown::spawn_signal_listener,
+        tls::{TlsFiles, load_tls_config, spawn_tls_reload_job},
+    },
+    services::{
+        job::{
+            account_deletion::spawn_account_deletion_job, expired_purge::spawn_expired_purge_job,
+        },
+        mail::build_mail_sender,
+        oidc::OidcClient,
+    },
 };
 
 mod api;
@@ -15,28 +34,206 @@ mod context;
 mod middleware;
 mod request;
 mod result;
+mod shutdown;
+mod tls;
 mod utils;
 
 pub async fn serve(config: Config, db: PgPool, start_time: Instant) -> anyhow::Result<()> {
-    let listener = TcpListener::bind("127.0.0.1:3000").await?;
+    // Without a forwarded address every socket client would share one IP and
+    // one login throttle.
+    if config.unix_socket_path.is_some() && !config.trust_forwarded_for {
+        anyhow::bail!("TRUST_FORWARDED_FOR must be true when UNIX_SOCKET_PATH is set");
+    }
+
+    let mailer = build_mail_sender(&config).context("cannot build mail sender")?;
+    let oidc = Arc::new(OidcClient::new(&config).context("cannot build OIDC client")?);
+    let tls_files = TlsFiles::from_config(&config);
+    let tls_config = match &tls_files {
+        Some(files) => Some(load_tls_config(files).await?),
+        None => None,
+    };
+    let listen_addr = SocketAddr::new(config.listen_host, config.listen_port);
+    let unix_socket_path = config.unix_socket_path.clone();
+    let tls_reload_interval_seconds = config.tls_reload_interval_seconds;
+    let drain_timeout = Duration::from_secs(config.shutdown_drain_timeout_seconds);
+    let config = Arc::new(config);
+
+    let shutdown = CancellationToken::new();
+    spawn_signal_listener(shutdown.clone());
+
+    let tasks = TaskTracker::new();
+    let mut jobs = vec![
+        spawn_account_deletion_job(db.clone(), config.clone(), shutdown.clone()),
+        spawn_expired_purge_job(db.clone(), config.clone(), shutdown.clone()),
+    ];
+
     let app = api_router()
         .layer(axum::middleware::from_fn(request_context_middleware))
+        .merge(api::health::router())
+        .merge(api::metrics::router())
         .layer(Extension(ApiContext {
-            config: Arc::new(config),
-            db: db,
+            config,
+            db: db.clone(),
+            mailer,
+            oidc,
+            shutdown: shutdown.clone(),
+            tasks: tasks.clone(),
         }));
 
-    tracing::info!(
-        "🚀 Server started at http://127.0.0.1:3000 by {} ms",
-        start_time.elapsed().as_millis()
-    );
-    axum::serve(listener, app)
+    // Unix socket peers have no address; behind a sidecar the client IP has
+    // to come from `X-Forwarded-For`.
+    let server: ServerFuture = if let Some(path) = unix_socket_path {
+        remove_stale_socket(&path)?;
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("cannot listen on {}", path.display()))?;
+
+        log_started("unix", &path.display().to_string(), start_time);
+        let shutdown = shutdown.clone();
+        Box::pin(async move {
+            axum::serve(listener, app.into_make_service())
+                .with_graceful_shutdown(shutdown.cancelled_owned())
+                .await
+                .context("cannot start http server")
+        })
+    } else if let (Some(tls_config), Some(tls_files)) = (tls_config, tls_files) {
+        jobs.push(spawn_tls_reload_job(
+            tls_config.clone(),
+            tls_files,
+            tls_reload_interval_seconds,
+            shutdown.clone(),
+        ));
+
+        let handle = Handle::new();
+        let shutdown_handle = handle.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            shutdown.cancelled().await;
+            shutdown_handle.graceful_shutdown(Some(drain_timeout));
+        });
+
+        log_started("https", &listen_addr.to_string(), start_time);
+        Box::pin(async move {
+            axum_server::bind_rustls(listen_addr, tls_config)
+                .handle(handle)
+                .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+                .await
+                .context("cannot start https server")
+        })
+    } else {
+        let listener = TcpListener::bind(listen_addr)
+            .await
+            .with_context(|| format!("cannot listen on {}", listen_addr))?;
+
+        log_started("http", &listen_addr.to_string(), start_time);
+        let shutdown = shutdown.clone();
+        Box::pin(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(shutdown.cancelled_owned())
+            .await
+            .context("cannot start http server")
+        })
+    };
+
+    run_until_drained(server, jobs, tasks, db, shutdown, drain_timeout).await
+}
+
+type ServerFuture = BoxFuture<'static, anyhow::Result<()>>;
+
+/// Serves until shutdown starts, then stops accepting connections and gives
+/// in-flight requests, the work they left running, the background jobs and
+/// the database pool one shared deadline to finish. Whatever is still running
+/// at the deadline is dropped.
+async fn run_until_drained(
+    mut server: ServerFuture,
+    jobs: Vec<JoinHandle<()>>,
+    tasks: TaskTracker,
+    db: PgPool,
+    shutdown: CancellationToken,
+    drain_timeout: Duration,
+) -> anyhow::Result<()> {
+    let stopped_early = tokio::select! {
+        result = &mut server => Some(result),
+        _ = shutdown.cancelled() => None,
+    };
+
+    // The server only stops by itself when it fails; the jobs stop with it.
+    shutdown.cancel();
+    let deadline = Instant::now() + drain_timeout;
+
+    let server_result = match stopped_early {
+        Some(result) => result,
+        None => {
+            tracing::info!(
+                "Draining in-flight requests for up to {} s",
+                drain_timeout.as_secs()
+            );
+            match tokio::time::timeout_at(deadline, server).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!("Drain deadline passed, dropping open connections");
+                    Ok(())
+                }
+            }
+        }
+    };
+
+    // No request is left to spawn more once the server has drained.
+    tasks.close();
+    if tokio::time::timeout_at(deadline, tasks.wait())
         .await
-        .context("cannot start http server")?;
+        .is_err()
+    {
+        tracing::warn!(
+            "{} request tasks still running at the drain deadline",
+            tasks.len()
+        );
+    }
+
+    for job in jobs {
+        if tokio::time::timeout_at(deadline, job).await.is_err() {
+            tracing::warn!("Background job still running at the drain deadline");
+        }
+    }
+
+    match tokio::time::timeout_at(deadline, db.close()).await {
+        Ok(()) => tracing::info!("Database pool closed"),
+        Err(_) => tracing::warn!("Database connections still in use at the drain deadline"),
+    }
 
-    Ok(())
+    server_result
 }
 
 fn api_router() -> Router {
     api::account::router()
+        .merge(api::password::router())
+        .merge(api::verification::router())
+        .merge(api::totp::router())
+        .merge(api::deletion::router())
+        .merge(api::export::router())
+        .merge(api::admin::router())
+        .merge(api::api_key::router())
+        .merge(api::login_link::router())
+        .merge(api::oidc::router())
+}
+
+fn log_started(scheme: &str, address: &str, start_time: Instant) {
+    tracing::info!(
+        "🚀 Server started at {}://{} by {} ms",
+        scheme,
+        address,
+        start_time.elapsed().as_millis()
+    );
+}
+
+/// A socket file left by a previous run makes binding fail. Anything that is
+/// not a socket is left alone.
+fn remove_stale_socket(path: &Path) -> anyhow::Result<()> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => std::fs::remove_file(path)
+            .with_context(|| format!("cannot remove stale socket {}", path.display())),
+        _ => Ok(()),
+    }
 }
@@ -4,9 +4,12 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::Deserialize;
 
-use crate::http::{
-    result::app_result::HttpError,
-    utils::{error::HttpErrorCase, scenario::HttpScenario, validator::ValidateFieldsJSON},
+use crate::{
+    http::{
+        result::app_result::HttpError,
+        utils::{error::HttpErrorCase, scenario::HttpScenario, validator::ValidateFieldsJSON},
+    },
+    services::utils::email::normalize_email,
 };
 
 static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[^\s@]+@[^\s@]+\.[^\s@]+$").unwrap());
@@ -31,11 +34,65 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginLinkRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyLoginLinkRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResendVerificationRequest {
+    pub email: String,
+}
+
 impl ValidateFieldsJSON for RegisterRequest {
     fn get_mandatory_field() -> Vec<&'static str> {
         vec!["email", "password"]
     }
 
+    fn normalize(&mut self) {
+        self.email = normalize_email(&self.email);
+    }
+
     fn validate_business_logic(&self) -> Result<(), HttpError> {
         if !EMAIL_REGEX.is_match(&self.email) {
             return Err(HttpError {
@@ -47,7 +104,7 @@ impl ValidateFieldsJSON for RegisterRequest {
             });
         }
 
-        check_password_requirements(&self.password)?;
+        check_password_requirements(&self.password, HttpScenario::Register)?;
 
         Ok(())
     }
@@ -58,6 +115,10 @@ impl ValidateFieldsJSON for LoginRequest {
         vec!["email", "password"]
     }
 
+    fn normalize(&mut self) {
+        self.email = normalize_email(&self.email);
+    }
+
     fn validate_business_logic(&self) -> Result<(), HttpError> {
         if !EMAIL_REGEX.is_match(&self.email) {
             return Err(HttpError {
@@ -73,12 +134,134 @@ impl ValidateFieldsJSON for LoginRequest {
     }
 }
 
-fn check_password_requirements(password: &str) -> Result<(), HttpError> {
+impl ValidateFieldsJSON for RefreshRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["refreshToken"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        Ok(())
+    }
+}
+
+impl ValidateFieldsJSON for ForgotPasswordRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["email"]
+    }
+
+    fn normalize(&mut self) {
+        self.email = normalize_email(&self.email);
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        if !EMAIL_REGEX.is_match(&self.email) {
+            return Err(HttpError {
+                status: 400,
+                scenario: HttpScenario::ForgotPassword,
+                case: HttpErrorCase::ZeroOne,
+                error_log: String::from("Email is not a valid email!"),
+                output: String::from("Invalid Field Format email"),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateFieldsJSON for LoginLinkRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["email"]
+    }
+
+    fn normalize(&mut self) {
+        self.email = normalize_email(&self.email);
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        if !EMAIL_REGEX.is_match(&self.email) {
+            return Err(HttpError {
+                status: 400,
+                scenario: HttpScenario::RequestLoginLink,
+                case: HttpErrorCase::ZeroOne,
+                error_log: String::from("Email is not a valid email!"),
+                output: String::from("Invalid Field Format email"),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateFieldsJSON for VerifyLoginLinkRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["token"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        Ok(())
+    }
+}
+
+impl ValidateFieldsJSON for ResetPasswordRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["token", "newPassword"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        check_password_requirements(&self.new_password, HttpScenario::ResetPassword)
+    }
+}
+
+impl ValidateFieldsJSON for ChangePasswordRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["currentPassword", "newPassword"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        check_password_requirements(&self.new_password, HttpScenario::ChangePassword)
+    }
+}
+
+impl ValidateFieldsJSON for VerifyEmailRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["token"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        Ok(())
+    }
+}
+
+impl ValidateFieldsJSON for ResendVerificationRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["email"]
+    }
+
+    fn normalize(&mut self) {
+        self.email = normalize_email(&self.email);
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        if !EMAIL_REGEX.is_match(&self.email) {
+            return Err(HttpError {
+                status: 400,
+                scenario: HttpScenario::ResendVerification,
+                case: HttpErrorCase::ZeroOne,
+                error_log: String::from("Email is not a valid email!"),
+                output: String::from("Invalid Field Format email"),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+fn check_password_requirements(password: &str, scenario: HttpScenario) -> Result<(), HttpError> {
     if password.len() < MINIMUM_LENGTH {
         let message: String = "Password must be at least 6 characters".into();
         return Err(HttpError {
             status: 400,
-            scenario: HttpScenario::Register,
+            scenario,
             case: HttpErrorCase::ZeroSix,
             error_log: message.clone(),
             output: message,
@@ -116,10 +299,14 @@ fn check_password_requirements(password: &str) -> Result<(), HttpError> {
         }
     }
 
-    check_requirement_count(matched_count, last_unmatched)
+    check_requirement_count(matched_count, last_unmatched, scenario)
 }
 
-fn check_requirement_count(count: usize, last: Option<&str>) -> Result<(), HttpError> {
+fn check_requirement_count(
+    count: usize,
+    last: Option<&str>,
+    scenario: HttpScenario,
+) -> Result<(), HttpError> {
     if count >= 3 {
         Ok(())
     } else {
@@ -129,7 +316,7 @@ fn check_requirement_count(count: usize, last: Option<&str>) -> Result<(), HttpE
         );
         Err(HttpError {
             status: 400,
-            scenario: HttpScenario::Register,
+            scenario,
             case: HttpErrorCase::ZeroSix,
             error_log: message.clone(),
             output: message,
@@ -0,0 +1,102 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Deserialize;
+
+use crate::{
+    dal::account::AccountQuery,
+    http::{
+        request::page::parse_page,
+        result::app_result::HttpError,
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+};
+
+/// Query string of `GET /admin/accounts`. Everything is read as text so bad
+/// values are reported in the usual response format.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAccountsQuery {
+    pub page: Option<String>,
+    pub page_size: Option<String>,
+    pub email: Option<String>,
+    pub created_from: Option<String>,
+    pub created_to: Option<String>,
+}
+
+pub struct AdminAccountsFilter<'a> {
+    pub query: AccountQuery<'a>,
+    pub page: i64,
+    pub page_size: i64,
+}
+
+impl AdminAccountsQuery {
+    pub fn validate(&self) -> Result<AdminAccountsFilter<'_>, HttpError> {
+        let page = parse_page(
+            self.page.as_deref(),
+            self.page_size.as_deref(),
+            HttpScenario::AdminListAccounts,
+        )?;
+
+        Ok(AdminAccountsFilter {
+            query: AccountQuery {
+                email: self
+                    .email
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|email| !email.is_empty()),
+                created_from: parse_time(
+                    self.created_from.as_deref(),
+                    "createdFrom",
+                    NaiveTime::MIN,
+                )?,
+                created_to: parse_time(
+                    self.created_to.as_deref(),
+                    "createdTo",
+                    NaiveTime::from_hms_micro_opt(23, 59, 59, 999_999).unwrap(),
+                )?,
+            },
+            page: page.page,
+            page_size: page.page_size,
+        })
+    }
+}
+
+/// Account ids come from the path as text for the same reason.
+pub fn parse_account_id(raw: &str, scenario: HttpScenario) -> Result<i32, HttpError> {
+    raw.parse::<i32>().map_err(|_| HttpError {
+        status: 400,
+        scenario,
+        case: HttpErrorCase::ZeroOne,
+        error_log: format!("Account id {} is not a number", raw),
+        output: String::from("Invalid Field Format accountId"),
+    })
+}
+
+/// Accepts an RFC 3339 timestamp or a plain `YYYY-MM-DD` date, which is read
+/// as `time_of_day` in UTC.
+fn parse_time(
+    raw: Option<&str>,
+    field: &str,
+    time_of_day: NaiveTime,
+) -> Result<Option<DateTime<Utc>>, HttpError> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(Some(time.with_timezone(&Utc)));
+    }
+
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map(|date| Some(date.and_time(time_of_day).and_utc()))
+        .map_err(|_| invalid_field(field, raw))
+}
+
+fn invalid_field(field: &str, raw: &str) -> HttpError {
+    HttpError {
+        status: 400,
+        scenario: HttpScenario::AdminListAccounts,
+        case: HttpErrorCase::ZeroOne,
+        error_log: format!("Invalid {}: {}", field, raw),
+        output: format!("Invalid Field Format {}", field),
+    }
+}
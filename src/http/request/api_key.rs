@@ -0,0 +1,84 @@
+use serde::Deserialize;
+
+use crate::{
+    http::{
+        result::app_result::HttpError,
+        utils::{error::HttpErrorCase, scenario::HttpScenario, validator::ValidateFieldsJSON},
+    },
+    services::model::api_key::ApiKeyScope,
+};
+
+const MAXIMUM_NAME_LENGTH: usize = 100;
+const MAXIMUM_EXPIRY_DAYS: i64 = 3650;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_in_days: Option<i64>,
+}
+
+impl CreateApiKeyRequest {
+    /// Only meaningful after validation, which rejects unknown scopes.
+    pub fn parsed_scopes(&self) -> Vec<ApiKeyScope> {
+        self.scopes
+            .iter()
+            .filter_map(|scope| ApiKeyScope::parse(scope))
+            .collect()
+    }
+}
+
+impl ValidateFieldsJSON for CreateApiKeyRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["name", "scopes"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        if self.name.trim().is_empty() || self.name.chars().count() > MAXIMUM_NAME_LENGTH {
+            return Err(invalid_field(
+                "name",
+                format!("API key name length is invalid: {}", self.name),
+            ));
+        }
+
+        if self.scopes.is_empty() {
+            return Err(invalid_field(
+                "scopes",
+                String::from("API key has no scope"),
+            ));
+        }
+
+        if let Some(scope) = self
+            .scopes
+            .iter()
+            .find(|scope| ApiKeyScope::parse(scope).is_none())
+        {
+            return Err(invalid_field(
+                "scopes",
+                format!("Unknown API key scope: {}", scope),
+            ));
+        }
+
+        if let Some(days) = self.expires_in_days
+            && !(1..=MAXIMUM_EXPIRY_DAYS).contains(&days)
+        {
+            return Err(invalid_field(
+                "expiresInDays",
+                format!("API key expiry out of range: {} days", days),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn invalid_field(field: &str, error_log: String) -> HttpError {
+    HttpError {
+        status: 400,
+        scenario: HttpScenario::CreateApiKey,
+        case: HttpErrorCase::ZeroOne,
+        error_log,
+        output: format!("Invalid Field Format {}", field),
+    }
+}
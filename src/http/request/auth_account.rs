@@ -0,0 +1,210 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::{
+    dal::{account::Account, api_key::ApiKey},
+    http::{
+        context::ApiContext,
+        request::auth_session::AuthSession,
+        result::app_result::HttpError,
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{
+        handler::api_key::{API_KEY_PREFIX, authenticate_api_key},
+        model::api_key::ApiKeyScope,
+        utils::error::AppError,
+    },
+};
+
+/// How the caller proved who they are.
+pub enum Credential {
+    Session,
+    ApiKey(ApiKey),
+}
+
+/// The caller of a route open to machine clients: a session like
+/// [`AuthSession`], or an API key sent as a bearer token.
+pub struct AuthAccount {
+    pub account: Account,
+    pub credential: Credential,
+}
+
+impl AuthAccount {
+    /// Sessions hold every scope; API keys only the ones they were minted with.
+    pub fn require_scope(
+        &self,
+        scope: ApiKeyScope,
+        scenario: HttpScenario,
+    ) -> Result<(), HttpError> {
+        let Credential::ApiKey(api_key) = &self.credential else {
+            return Ok(());
+        };
+
+        if api_key
+            .scopes
+            .iter()
+            .any(|granted| granted == scope.as_str())
+        {
+            return Ok(());
+        }
+
+        Err(HttpError {
+            status: 403,
+            scenario,
+            case: HttpErrorCase::OneThree,
+            error_log: format!("API key {} lacks scope {}", api_key.id, scope.as_str()),
+            output: String::from("Forbidden"),
+        })
+    }
+}
+
+impl FromRequestParts<()> for AuthAccount {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &()) -> Result<Self, Self::Rejection> {
+        let api_key = parts
+            .headers
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|v| v.starts_with(API_KEY_PREFIX))
+            .map(str::to_string);
+
+        let Some(api_key) = api_key else {
+            let auth = AuthSession::from_request_parts(parts, state).await?;
+            return Ok(AuthAccount {
+                account: auth.account,
+                credential: Credential::Session,
+            });
+        };
+
+        let scenario = HttpScenario::from_request(&parts.method, parts.uri.path());
+
+        let Some(ctx) = parts.extensions.get::<ApiContext>().cloned() else {
+            return Err(HttpError {
+                status: 500,
+                scenario,
+                case: HttpErrorCase::ZeroOne,
+                error_log: "ApiContext extension is missing".to_string(),
+                output: "Internal Server Error".to_string(),
+            });
+        };
+
+        let (account, api_key) = authenticate_api_key(&ctx.db, &api_key)
+            .await
+            .map_err(|err| match err {
+                AppError::InvalidApiKey { msg } => HttpError {
+                    status: 401,
+                    scenario,
+                    case: HttpErrorCase::ZeroTwo,
+                    error_log: format!("Invalid API key: {}", msg),
+                    output: "Invalid API Key".to_string(),
+                },
+                other => HttpError {
+                    status: 500,
+                    scenario,
+                    case: HttpErrorCase::ZeroOne,
+                    error_log: format!("Unexpected error: {:?}", other),
+                    output: "Internal Server Error".to_string(),
+                },
+            })?;
+
+        Ok(AuthAccount {
+            account,
+            credential: Credential::ApiKey(api_key),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::dal::account::AccountRole;
+
+    fn auth_account(credential: Credential) -> AuthAccount {
+        let now = Utc::now();
+        AuthAccount {
+            account: Account {
+                id: 1,
+                email: String::from("a@b.io"),
+                password: String::new(),
+                utc_create: now,
+                utc_modified: now,
+                verified_at: Some(now),
+                failed_login_count: 0,
+                lockout_count: 0,
+                locked_until: None,
+                delete_after: None,
+                role: AccountRole::Admin,
+                disabled_at: None,
+            },
+            credential,
+        }
+    }
+
+    fn api_key(scopes: &[&str]) -> Credential {
+        let now = Utc::now();
+        Credential::ApiKey(ApiKey {
+            id: 7,
+            account_id: 1,
+            name: String::from("ci"),
+            key_prefix: String::from("pnp_abcd"),
+            key_hash: String::new(),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expiry_time: None,
+            last_used_time: None,
+            revoked_time: None,
+            utc_create: now,
+            utc_modified: now,
+        })
+    }
+
+    #[test]
+    fn sessions_hold_every_scope() {
+        let auth = auth_account(Credential::Session);
+
+        assert!(
+            auth.require_scope(ApiKeyScope::Admin, HttpScenario::Index)
+                .is_ok()
+        );
+        assert!(
+            auth.require_scope(ApiKeyScope::AccountRead, HttpScenario::Index)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn api_keys_hold_only_their_granted_scopes() {
+        let auth = auth_account(api_key(&["account:read"]));
+
+        assert!(
+            auth.require_scope(ApiKeyScope::AccountRead, HttpScenario::Index)
+                .is_ok()
+        );
+        let rejection = auth
+            .require_scope(ApiKeyScope::Admin, HttpScenario::Index)
+            .unwrap_err();
+        assert_eq!(rejection.status, 403);
+    }
+
+    #[test]
+    fn admin_scope_does_not_imply_account_read() {
+        let auth = auth_account(api_key(&["admin"]));
+
+        assert!(
+            auth.require_scope(ApiKeyScope::AccountRead, HttpScenario::Index)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn roles_are_ordered_by_privilege() {
+        assert!(AccountRole::User < AccountRole::Admin);
+        assert_eq!(
+            [AccountRole::Admin, AccountRole::User].iter().max(),
+            Some(&AccountRole::Admin)
+        );
+    }
+}
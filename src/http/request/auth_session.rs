@@ -0,0 +1,88 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{HeaderMap, request::Parts},
+};
+
+use crate::{
+    dal::{account::Account, session::Session},
+    http::{
+        context::ApiContext,
+        result::app_result::HttpError,
+        utils::{cookie::read_cookie, error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::{handler::session::authenticate_session, utils::error::AppError},
+};
+
+const SESSION_COOKIE_NAME: &str = "session_id";
+
+/// The caller of a protected route, resolved from a bearer token or the
+/// `session_id` cookie.
+pub struct AuthSession {
+    pub account: Account,
+    pub session: Session,
+}
+
+impl FromRequestParts<()> for AuthSession {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &()) -> Result<Self, Self::Rejection> {
+        let scenario = HttpScenario::from_request(&parts.method, parts.uri.path());
+
+        let Some(ctx) = parts.extensions.get::<ApiContext>().cloned() else {
+            return Err(HttpError {
+                status: 500,
+                scenario,
+                case: HttpErrorCase::ZeroOne,
+                error_log: "ApiContext extension is missing".to_string(),
+                output: "Internal Server Error".to_string(),
+            });
+        };
+
+        let Some(session_id) = extract_session_id(&parts.headers) else {
+            return Err(HttpError {
+                status: 401,
+                scenario,
+                case: HttpErrorCase::ZeroZero,
+                error_log: "Missing bearer token or session cookie".to_string(),
+                output: "Unauthorized".to_string(),
+            });
+        };
+
+        let (account, session) =
+            authenticate_session(&ctx.db, &session_id)
+                .await
+                .map_err(|err| match err {
+                    AppError::InvalidSession { msg } => HttpError {
+                        status: 401,
+                        scenario,
+                        case: HttpErrorCase::ZeroTwo,
+                        error_log: format!("Invalid session: {}", msg),
+                        output: "Invalid Session Token".to_string(),
+                    },
+                    other => HttpError {
+                        status: 500,
+                        scenario,
+                        case: HttpErrorCase::ZeroOne,
+                        error_log: format!("Unexpected error: {:?}", other),
+                        output: "Internal Server Error".to_string(),
+                    },
+                })?;
+
+        Ok(AuthSession { account, session })
+    }
+}
+
+fn extract_session_id(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+
+    if bearer.is_some() {
+        return bearer;
+    }
+
+    read_cookie(headers, SESSION_COOKIE_NAME)
+}
@@ -1,2 +1,11 @@
 pub mod account;
+pub mod admin;
+pub mod api_key;
+pub mod auth_account;
+pub mod auth_session;
+pub mod export;
+pub mod oidc;
+pub mod page;
+pub mod require_role;
 pub mod safe_json;
+pub mod totp;
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+use crate::{
+    http::{
+        result::app_result::HttpError,
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::model::oidc::OidcCallback,
+};
+
+/// Query string the provider redirects back with: either `code` and `state`,
+/// or an `error` when the login was refused.
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+impl OidcCallbackQuery {
+    /// `browser_state` is the state cookie the request came with.
+    pub fn validate<'a>(
+        &'a self,
+        browser_state: Option<&'a str>,
+    ) -> Result<OidcCallback<'a>, HttpError> {
+        if let Some(error) = &self.error {
+            return Err(HttpError {
+                status: 400,
+                scenario: HttpScenario::OidcCallback,
+                case: HttpErrorCase::ZeroFive,
+                error_log: format!(
+                    "Provider refused the login: {} {}",
+                    error,
+                    self.error_description.as_deref().unwrap_or_default()
+                ),
+                output: String::from("OIDC login failed"),
+            });
+        }
+
+        Ok(OidcCallback {
+            code: mandatory(self.code.as_deref(), "code")?,
+            state: mandatory(self.state.as_deref(), "state")?,
+            browser_state,
+        })
+    }
+}
+
+fn mandatory<'a>(value: Option<&'a str>, field: &str) -> Result<&'a str, HttpError> {
+    value
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| HttpError {
+            status: 400,
+            scenario: HttpScenario::OidcCallback,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Missing query parameter {}", field),
+            output: format!("Invalid Field Format {}", field),
+        })
+}
@@ -0,0 +1,88 @@
+use serde::Deserialize;
+
+use crate::http::{
+    result::app_result::HttpError,
+    utils::{error::HttpErrorCase, scenario::HttpScenario},
+};
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAXIMUM_PAGE_SIZE: i64 = 100;
+/// Keeps `(page - 1) * page_size` far from overflowing an `i64` OFFSET.
+const MAXIMUM_PAGE: i64 = 1_000_000;
+
+/// Query string of the paged listings that take no other filter. Values are
+/// read as text so bad ones are reported in the usual response format.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageQuery {
+    pub page: Option<String>,
+    pub page_size: Option<String>,
+}
+
+pub struct Page {
+    pub page: i64,
+    pub page_size: i64,
+}
+
+impl PageQuery {
+    pub fn validate(&self, scenario: HttpScenario) -> Result<Page, HttpError> {
+        parse_page(self.page.as_deref(), self.page_size.as_deref(), scenario)
+    }
+}
+
+/// Pages start at 1 and stop at 1,000,000; the page size defaults to 20 and
+/// is capped at 100.
+pub fn parse_page(
+    page: Option<&str>,
+    page_size: Option<&str>,
+    scenario: HttpScenario,
+) -> Result<Page, HttpError> {
+    let page = parse_positive(page, "page", 1, MAXIMUM_PAGE, scenario)?;
+    let page_size = parse_positive(page_size, "pageSize", DEFAULT_PAGE_SIZE, i64::MAX, scenario)?
+        .min(MAXIMUM_PAGE_SIZE);
+
+    Ok(Page { page, page_size })
+}
+
+fn parse_positive(
+    raw: Option<&str>,
+    field: &str,
+    default: i64,
+    maximum: i64,
+    scenario: HttpScenario,
+) -> Result<i64, HttpError> {
+    let Some(raw) = raw else {
+        return Ok(default);
+    };
+
+    match raw.parse::<i64>() {
+        Ok(value) if (1..=maximum).contains(&value) => Ok(value),
+        _ => Err(HttpError {
+            status: 400,
+            scenario,
+            case: HttpErrorCase::ZeroOne,
+            error_log: format!("Invalid {}: {}", field, raw),
+            output: format!("Invalid Field Format {}", field),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_and_caps_the_page_size() {
+        let page = parse_page(None, Some("500"), HttpScenario::Index).unwrap();
+
+        assert_eq!((page.page, page.page_size), (1, MAXIMUM_PAGE_SIZE));
+    }
+
+    #[test]
+    fn rejects_pages_past_the_maximum() {
+        assert!(parse_page(Some("1000000"), None, HttpScenario::Index).is_ok());
+        assert!(parse_page(Some("1000001"), None, HttpScenario::Index).is_err());
+        assert!(parse_page(Some("9223372036854775807"), None, HttpScenario::Index).is_err());
+        assert!(parse_page(Some("0"), None, HttpScenario::Index).is_err());
+    }
+}
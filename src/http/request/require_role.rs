@@ -0,0 +1,67 @@
+use std::marker::PhantomData;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+use crate::{
+    dal::account::AccountRole,
+    http::{
+        request::auth_account::AuthAccount,
+        result::app_result::HttpError,
+        utils::{error::HttpErrorCase, scenario::HttpScenario},
+    },
+    services::model::api_key::ApiKeyScope,
+};
+
+/// A role a route can demand through [`RequireRole`], and the scope an API
+/// key of such an account needs to act in that role.
+pub trait RequiredRole {
+    const ROLE: AccountRole;
+    const SCOPE: ApiKeyScope;
+}
+
+pub struct AdminRole;
+
+impl RequiredRole for AdminRole {
+    const ROLE: AccountRole = AccountRole::Admin;
+    const SCOPE: ApiKeyScope = ApiKeyScope::Admin;
+}
+
+/// An [`AuthAccount`] whose account holds at least the role `R`. Callers
+/// below it are rejected with 403.
+pub struct RequireRole<R: RequiredRole> {
+    pub auth: AuthAccount,
+    role: PhantomData<R>,
+}
+
+pub type AdminSession = RequireRole<AdminRole>;
+
+impl<R: RequiredRole> FromRequestParts<()> for RequireRole<R> {
+    type Rejection = HttpError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &()) -> Result<Self, Self::Rejection> {
+        let auth = AuthAccount::from_request_parts(parts, state).await?;
+        let scenario = HttpScenario::from_request(&parts.method, parts.uri.path());
+
+        if auth.account.role < R::ROLE {
+            return Err(HttpError {
+                status: 403,
+                scenario,
+                case: HttpErrorCase::OneZero,
+                error_log: format!(
+                    "Account {} with role {:?} needs role {:?}",
+                    auth.account.id,
+                    auth.account.role,
+                    R::ROLE
+                ),
+                output: String::from("Forbidden"),
+            });
+        }
+
+        auth.require_scope(R::SCOPE, scenario)?;
+
+        Ok(RequireRole {
+            auth,
+            role: PhantomData,
+        })
+    }
+}
@@ -16,9 +16,9 @@ where
 {
     type Rejection = HttpError;
 
-    async fn from_request(req: Request, state: &()) -> Result<Self, Self::Rejection> {
+    async fn from_request(req: Request, _state: &()) -> Result<Self, Self::Rejection> {
         let path = req.uri().path();
-        let scenario = path_to_scenario(path);
+        let scenario = HttpScenario::from_request(req.method(), path);
 
         let (parts, body) = req.into_parts();
         let bytes = match body.collect().await {
@@ -74,7 +74,7 @@ where
             });
         }
 
-        let deserialized_value = match serde_json::from_value::<T>(json_value) {
+        let mut deserialized_value = match serde_json::from_value::<T>(json_value) {
             Ok(value) => value,
             Err(err) => {
                 let error_message = format!("Invalid JSON data: {}", err);
@@ -88,16 +88,9 @@ where
             }
         };
 
+        deserialized_value.normalize();
         deserialized_value.validate_business_logic()?;
 
         Ok(SafeJson(deserialized_value))
     }
 }
-
-fn path_to_scenario(path: &str) -> HttpScenario {
-    match path {
-        "/account/register" => HttpScenario::Register,
-        "/account/login" => HttpScenario::Login,
-        _ => HttpScenario::Index, // Default fallback
-    }
-}
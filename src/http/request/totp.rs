@@ -0,0 +1,71 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::http::{
+    result::app_result::HttpError,
+    utils::{error::HttpErrorCase, scenario::HttpScenario, validator::ValidateFieldsJSON},
+};
+
+static TOTP_CODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[0-9]{6}$").unwrap());
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpConfirmRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpLoginRequest {
+    pub challenge_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+impl ValidateFieldsJSON for TotpConfirmRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["code"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        check_totp_code(&self.code, HttpScenario::TotpConfirm)
+    }
+}
+
+impl ValidateFieldsJSON for TotpLoginRequest {
+    fn get_mandatory_field() -> Vec<&'static str> {
+        vec!["challengeToken"]
+    }
+
+    fn validate_business_logic(&self) -> Result<(), HttpError> {
+        match (&self.code, &self.recovery_code) {
+            (Some(code), None) => check_totp_code(code, HttpScenario::LoginTotp),
+            (None, Some(_)) => Ok(()),
+            _ => {
+                let message = String::from("Exactly one of code or recoveryCode is required");
+                Err(HttpError {
+                    status: 400,
+                    scenario: HttpScenario::LoginTotp,
+                    case: HttpErrorCase::ZeroOne,
+                    error_log: message.clone(),
+                    output: message,
+                })
+            }
+        }
+    }
+}
+
+fn check_totp_code(code: &str, scenario: HttpScenario) -> Result<(), HttpError> {
+    if !TOTP_CODE_REGEX.is_match(code) {
+        return Err(HttpError {
+            status: 400,
+            scenario,
+            case: HttpErrorCase::ZeroOne,
+            error_log: String::from("TOTP code is not 6 digits"),
+            output: String::from("Invalid Field Format code"),
+        });
+    }
+
+    Ok(())
+}
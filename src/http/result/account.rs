@@ -1,4 +1,11 @@
-use crate::services::model::account::{LoggedAccount, SavedAccount};
+use crate::{
+    http::{result::app_result::ApiResponse, utils::scenario::HttpScenario},
+    services::model::{
+        account::{AccountProfile, LoggedAccount, LoginOutcome, SavedAccount, ScheduledDeletion},
+        auth_event::AuthEventPage,
+        totp::TotpChallenge,
+    },
+};
 
 #[derive(serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -9,5 +16,73 @@ pub struct RegisterResult {
 #[derive(serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logged_account: Option<LoggedAccount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub totp_challenge: Option<TotpChallenge>,
+}
+
+impl LoginResult {
+    /// A finished login answers `200xx00`; one waiting for a second factor
+    /// answers `200xx01` and carries the challenge instead of a session.
+    pub fn into_api_response(outcome: LoginOutcome, scenario: HttpScenario) -> ApiResponse<Self> {
+        match outcome {
+            LoginOutcome::LoggedIn(logged_account) => ApiResponse {
+                response_code: format!("200{}00", scenario.get_code()),
+                response_message: String::from("Successful"),
+                data: LoginResult {
+                    logged_account: Some(logged_account),
+                    totp_challenge: None,
+                },
+            },
+            LoginOutcome::TotpRequired(totp_challenge) => ApiResponse {
+                response_code: format!("200{}01", scenario.get_code()),
+                response_message: String::from("TOTP Required"),
+                data: LoginResult {
+                    logged_account: None,
+                    totp_challenge: Some(totp_challenge),
+                },
+            },
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginLinkResult {}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileResult {
+    pub account_profile: AccountProfile,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshResult {
     pub logged_account: LoggedAccount,
 }
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailResult {}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResendVerificationResult {}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAccountResult {
+    pub scheduled_deletion: ScheduledDeletion,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelDeletionResult {}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityResult {
+    pub activity: AuthEventPage,
+}
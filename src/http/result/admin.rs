@@ -0,0 +1,33 @@
+use crate::services::model::{
+    admin::{AdminAccountDetail, AdminAccountPage},
+    auth_event::AuthEventPage,
+    session::RevokedSessions,
+};
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAccountsResult {
+    pub account_page: AdminAccountPage,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAccountResult {
+    pub account_detail: AdminAccountDetail,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminActivityResult {
+    pub activity: AuthEventPage,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminRevokeResult {
+    pub revoked_sessions: RevokedSessions,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminActionResult {}
@@ -0,0 +1,17 @@
+use crate::services::model::api_key::{ApiKeySummary, CreatedApiKey};
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyResult {
+    pub api_key: CreatedApiKey,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeysResult {
+    pub api_keys: Vec<ApiKeySummary>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokeApiKeyResult {}
@@ -0,0 +1,7 @@
+use crate::services::model::export::DataExportJob;
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataExportResult {
+    pub data_export: DataExportJob,
+}
@@ -0,0 +1,7 @@
+use crate::services::model::health::HealthStatus;
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LivenessResult {
+    pub status: HealthStatus,
+}
@@ -1,2 +1,10 @@
 pub mod account;
+pub mod admin;
+pub mod api_key;
 pub mod app_result;
+pub mod export;
+pub mod health;
+pub mod oidc;
+pub mod password;
+pub mod session;
+pub mod totp;
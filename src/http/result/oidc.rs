@@ -0,0 +1,7 @@
+use crate::services::model::oidc::OidcAuthorization;
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcAuthorizeResult {
+    pub oidc_authorization: OidcAuthorization,
+}
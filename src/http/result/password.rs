@@ -0,0 +1,17 @@
+use crate::services::model::session::RevokedSessions;
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordResult {}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordResult {
+    pub revoked_sessions: RevokedSessions,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordResult {
+    pub revoked_sessions: RevokedSessions,
+}
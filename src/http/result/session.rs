@@ -0,0 +1,13 @@
+use crate::services::model::session::{ActiveSession, RevokedSessions};
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutResult {
+    pub revoked_sessions: RevokedSessions,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsResult {
+    pub sessions: Vec<ActiveSession>,
+}
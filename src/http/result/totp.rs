@@ -0,0 +1,13 @@
+use crate::services::model::totp::TotpSetup;
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpSetupResult {
+    pub totp_setup: TotpSetup,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpConfirmResult {
+    pub recovery_codes: Vec<String>,
+}
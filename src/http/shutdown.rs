@@ -0,0 +1,25 @@
+use tokio::signal::unix::{SignalKind, signal};
+use tokio_util::sync::CancellationToken;
+
+/// Cancels `shutdown` on the first SIGTERM or SIGINT. Everything that has to
+/// stop with the server, the listeners, readiness and the background jobs,
+/// watches that token.
+pub fn spawn_signal_listener(shutdown: CancellationToken) {
+    tokio::spawn(async move {
+        let mut terminate = match signal(SignalKind::terminate()) {
+            Ok(terminate) => terminate,
+            Err(err) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", err);
+                return;
+            }
+        };
+
+        let signal_name = tokio::select! {
+            _ = terminate.recv() => "SIGTERM",
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+        };
+
+        tracing::info!("Received {}, shutting down", signal_name);
+        shutdown.cancel();
+    });
+}
@@ -0,0 +1,101 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use axum_server::tls_rustls::RustlsConfig;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Config;
+
+/// Certificate and key files TLS is served from.
+#[derive(Clone, Debug)]
+pub struct TlsFiles {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl TlsFiles {
+    pub fn from_config(config: &Config) -> Option<Self> {
+        Some(Self {
+            cert_path: config.tls_cert_path.clone()?,
+            key_path: config.tls_key_path.clone()?,
+        })
+    }
+
+    fn modified_times(&self) -> Option<(SystemTime, SystemTime)> {
+        Some((
+            modified_time(&self.cert_path)?,
+            modified_time(&self.key_path)?,
+        ))
+    }
+}
+
+pub async fn load_tls_config(files: &TlsFiles) -> anyhow::Result<RustlsConfig> {
+    // rustls needs a process-wide crypto provider; an error only means one
+    // is installed already.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    RustlsConfig::from_pem_file(&files.cert_path, &files.key_path)
+        .await
+        .with_context(|| {
+            format!(
+                "cannot load TLS certificate {} and key {}",
+                files.cert_path.display(),
+                files.key_path.display()
+            )
+        })
+}
+
+/// Watches the certificate and key files and swaps them into `tls_config`
+/// when either changes. Open connections keep the certificate they were
+/// accepted with; new ones get the replacement. A pair that fails to load,
+/// e.g. because only one of the files has been rewritten so far, is retried
+/// on the next check while the previous certificate stays in use.
+pub fn spawn_tls_reload_job(
+    tls_config: RustlsConfig,
+    files: TlsFiles,
+    interval_seconds: u64,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut loaded = files.modified_times();
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_seconds));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            let current = files.modified_times();
+            if current.is_none() || current == loaded {
+                continue;
+            }
+
+            match tls_config
+                .reload_from_pem_file(&files.cert_path, &files.key_path)
+                .await
+            {
+                Ok(()) => {
+                    loaded = current;
+                    tracing::info!(
+                        "Reloaded TLS certificate from {}",
+                        files.cert_path.display()
+                    );
+                }
+                Err(err) => tracing::error!("Failed to reload TLS certificate: {}", err),
+            }
+        }
+    })
+}
+
+/// Follows symlinks, so a mounted secret whose link is swapped counts as changed.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
@@ -0,0 +1,14 @@
+use axum::http::HeaderMap;
+
+/// Value of the named cookie, when the request carries a non-empty one.
+pub fn read_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all("cookie")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value.to_string())
+        .filter(|v| !v.is_empty())
+}
@@ -2,9 +2,18 @@
 pub enum HttpErrorCase {
     ZeroZero,
     ZeroOne,
+    ZeroTwo,
     ZeroThree,
     ZeroFour,
+    ZeroFive,
     ZeroSix,
+    ZeroSeven,
+    ZeroEight,
+    ZeroNine,
+    OneZero,
+    OneOne,
+    OneTwo,
+    OneThree,
 }
 
 impl HttpErrorCase {
@@ -12,9 +21,18 @@ impl HttpErrorCase {
         match self {
             HttpErrorCase::ZeroZero => String::from("00"),
             HttpErrorCase::ZeroOne => String::from("01"),
+            HttpErrorCase::ZeroTwo => String::from("02"),
             HttpErrorCase::ZeroThree => String::from("03"),
             HttpErrorCase::ZeroSix => String::from("06"),
             HttpErrorCase::ZeroFour => String::from("04"),
+            HttpErrorCase::ZeroFive => String::from("05"),
+            HttpErrorCase::ZeroSeven => String::from("07"),
+            HttpErrorCase::ZeroEight => String::from("08"),
+            HttpErrorCase::ZeroNine => String::from("09"),
+            HttpErrorCase::OneZero => String::from("10"),
+            HttpErrorCase::OneOne => String::from("11"),
+            HttpErrorCase::OneTwo => String::from("12"),
+            HttpErrorCase::OneThree => String::from("13"),
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod cookie;
 pub mod error;
 pub mod scenario;
 pub mod validator;
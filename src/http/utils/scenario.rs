@@ -1,8 +1,43 @@
-#[derive(Debug)]
+use axum::http::Method;
+
+#[derive(Clone, Copy, Debug)]
 pub enum HttpScenario {
     Index,
     Register,
     Login,
+    Profile,
+    Logout,
+    LogoutAll,
+    Sessions,
+    Refresh,
+    ForgotPassword,
+    ResetPassword,
+    VerifyEmail,
+    ResendVerification,
+    TotpSetup,
+    TotpConfirm,
+    LoginTotp,
+    ChangePassword,
+    DeleteAccount,
+    CancelDeletion,
+    RequestDataExport,
+    DataExportStatus,
+    DownloadDataExport,
+    AdminListAccounts,
+    AdminViewAccount,
+    AdminDisableAccount,
+    AdminEnableAccount,
+    AdminLogoutAccount,
+    AdminResetPassword,
+    CreateApiKey,
+    ListApiKeys,
+    RevokeApiKey,
+    OidcAuthorize,
+    OidcCallback,
+    RequestLoginLink,
+    LoginWithLink,
+    AccountActivity,
+    AdminAccountActivity,
 }
 
 impl HttpScenario {
@@ -11,6 +46,96 @@ impl HttpScenario {
             HttpScenario::Index => String::from("00"),
             HttpScenario::Register => String::from("13"),
             HttpScenario::Login => String::from("14"),
+            HttpScenario::Profile => String::from("15"),
+            HttpScenario::Logout => String::from("16"),
+            HttpScenario::LogoutAll => String::from("17"),
+            HttpScenario::Sessions => String::from("18"),
+            HttpScenario::Refresh => String::from("19"),
+            HttpScenario::ForgotPassword => String::from("20"),
+            HttpScenario::ResetPassword => String::from("21"),
+            HttpScenario::VerifyEmail => String::from("22"),
+            HttpScenario::ResendVerification => String::from("23"),
+            HttpScenario::TotpSetup => String::from("24"),
+            HttpScenario::TotpConfirm => String::from("25"),
+            HttpScenario::LoginTotp => String::from("26"),
+            HttpScenario::ChangePassword => String::from("27"),
+            HttpScenario::DeleteAccount => String::from("28"),
+            HttpScenario::CancelDeletion => String::from("29"),
+            HttpScenario::RequestDataExport => String::from("30"),
+            HttpScenario::DataExportStatus => String::from("31"),
+            HttpScenario::DownloadDataExport => String::from("32"),
+            HttpScenario::AdminListAccounts => String::from("33"),
+            HttpScenario::AdminViewAccount => String::from("34"),
+            HttpScenario::AdminDisableAccount => String::from("35"),
+            HttpScenario::AdminEnableAccount => String::from("36"),
+            HttpScenario::AdminLogoutAccount => String::from("37"),
+            HttpScenario::AdminResetPassword => String::from("38"),
+            HttpScenario::CreateApiKey => String::from("39"),
+            HttpScenario::ListApiKeys => String::from("40"),
+            HttpScenario::RevokeApiKey => String::from("41"),
+            HttpScenario::OidcAuthorize => String::from("42"),
+            HttpScenario::OidcCallback => String::from("43"),
+            HttpScenario::RequestLoginLink => String::from("44"),
+            HttpScenario::LoginWithLink => String::from("45"),
+            HttpScenario::AccountActivity => String::from("46"),
+            HttpScenario::AdminAccountActivity => String::from("47"),
+        }
+    }
+
+    /// Resolves the scenario of a request. Most paths serve one method; the
+    /// few that serve both a listing and a creation are told apart by it.
+    pub fn from_request(method: &Method, path: &str) -> HttpScenario {
+        match (method, path) {
+            (&Method::GET, "/account/export") => HttpScenario::DataExportStatus,
+            (&Method::GET, "/account/api-keys") => HttpScenario::ListApiKeys,
+            _ => HttpScenario::from_path(path),
+        }
+    }
+
+    fn from_path(path: &str) -> HttpScenario {
+        match path {
+            "/account/register" => HttpScenario::Register,
+            "/account/login" => HttpScenario::Login,
+            "/account/me" => HttpScenario::Profile,
+            "/account/logout" => HttpScenario::Logout,
+            "/account/logout-all" => HttpScenario::LogoutAll,
+            "/account/sessions" => HttpScenario::Sessions,
+            "/account/refresh" => HttpScenario::Refresh,
+            "/account/password/forgot" => HttpScenario::ForgotPassword,
+            "/account/password/reset" => HttpScenario::ResetPassword,
+            "/account/verify" => HttpScenario::VerifyEmail,
+            "/account/verify/resend" => HttpScenario::ResendVerification,
+            "/account/2fa/totp/setup" => HttpScenario::TotpSetup,
+            "/account/2fa/totp/confirm" => HttpScenario::TotpConfirm,
+            "/account/login/totp" => HttpScenario::LoginTotp,
+            "/account/password" => HttpScenario::ChangePassword,
+            "/account" => HttpScenario::DeleteAccount,
+            "/account/deletion/cancel" => HttpScenario::CancelDeletion,
+            "/account/export" => HttpScenario::RequestDataExport,
+            "/account/export/download" => HttpScenario::DownloadDataExport,
+            "/admin/accounts" => HttpScenario::AdminListAccounts,
+            "/account/api-keys" => HttpScenario::CreateApiKey,
+            "/account/login/link" => HttpScenario::RequestLoginLink,
+            "/account/login/link/verify" => HttpScenario::LoginWithLink,
+            "/account/activity" => HttpScenario::AccountActivity,
+            _ => HttpScenario::from_path_segments(path),
+        }
+    }
+
+    /// Paths carrying an id, matched segment by segment.
+    fn from_path_segments(path: &str) -> HttpScenario {
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match segments.as_slice() {
+            ["admin", "accounts", _] => HttpScenario::AdminViewAccount,
+            ["admin", "accounts", _, "disable"] => HttpScenario::AdminDisableAccount,
+            ["admin", "accounts", _, "enable"] => HttpScenario::AdminEnableAccount,
+            ["admin", "accounts", _, "logout"] => HttpScenario::AdminLogoutAccount,
+            ["admin", "accounts", _, "password-reset"] => HttpScenario::AdminResetPassword,
+            ["admin", "accounts", _, "activity"] => HttpScenario::AdminAccountActivity,
+            ["account", "api-keys", _] => HttpScenario::RevokeApiKey,
+            ["account", "oidc", _, "authorize"] => HttpScenario::OidcAuthorize,
+            ["account", "oidc", _, "callback"] => HttpScenario::OidcCallback,
+            _ => HttpScenario::Index, // Default fallback
         }
     }
 }
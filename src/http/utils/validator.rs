@@ -34,5 +34,8 @@ pub trait ValidateFieldsJSON {
 
     fn get_mandatory_field() -> Vec<&'static str>;
 
+    /// Rewrites fields into their canonical form before they are validated.
+    fn normalize(&mut self) {}
+
     fn validate_business_logic(&self) -> Result<(), HttpError>;
 }
@@ -2,10 +2,15 @@ use std::time::Duration;
 
 use anyhow::Context;
 use clap::Parser;
+use opentelemetry::trace::TracerProvider;
 use plug_and_plant_be_axum_sqlx::config::Config;
-use plug_and_plant_be_axum_sqlx::http;
+use plug_and_plant_be_axum_sqlx::dal::migration::MIGRATOR;
+use plug_and_plant_be_axum_sqlx::services::metrics::pool_acquire_layer;
+use plug_and_plant_be_axum_sqlx::services::telemetry::{self, TRACER_NAME};
+use plug_and_plant_be_axum_sqlx::{cli, http};
 use sqlx::postgres::PgPoolOptions;
 use tokio::time::Instant;
+use tracing_log::log::LevelFilter;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
 
@@ -23,19 +28,27 @@ async fn main() -> anyhow::Result<()> {
 "#
     );
     dotenv::dotenv().ok();
-    let config = Config::parse();
+    let mut config = Config::parse();
 
-    // Initialize tracing subscriber with log capture
+    let tracer_provider = telemetry::init_tracer_provider(&config)?;
+    let otel_layer = tracer_provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(TRACER_NAME)));
+
+    // Initialize tracing subscriber with log capture. `RUST_LOG` filters the
+    // exported spans and the log output; the pool metrics filter for themselves.
     tracing_subscriber::registry()
-        .with(EnvFilter::from_default_env())
+        .with(otel_layer.with_filter(EnvFilter::from_default_env()))
         .with(
             tracing_subscriber::fmt::layer()
                 .with_thread_ids(true)
                 .with_thread_names(true)
                 .with_level(true)
                 .with_file(true)
-                .with_line_number(true),
+                .with_line_number(true)
+                .with_filter(EnvFilter::from_default_env()),
         )
+        .with(pool_acquire_layer())
         .try_init()
         .context("failed to initialize tracing subscriber")?;
 
@@ -44,15 +57,25 @@ async fn main() -> anyhow::Result<()> {
         .acquire_timeout(Duration::from_secs(30))
         .idle_timeout(Duration::from_secs(600))
         .max_lifetime(Duration::from_secs(1800))
+        // Reports the wait of every acquire to the pool metrics.
+        .acquire_time_level(LevelFilter::Trace)
         .connect(&config.database_url)
         .await
         .context("could not connect to database_url")?;
 
-    // This embeds database migrations in the application binary so we can ensure the database
-    // is migrated correctly on startup
-    sqlx::migrate!("./migrations").run(&db).await?;
+    MIGRATOR.run(&db).await?;
+
+    let result = match config.command.take() {
+        Some(command) => cli::run_command(command, &db).await,
+        None => http::serve(config, db, start_time).await,
+    };
 
-    http::serve(config, db, start_time).await?;
+    // Flush the spans still buffered in the batch exporter.
+    if let Some(tracer_provider) = tracer_provider
+        && let Err(error) = tracer_provider.shutdown()
+    {
+        tracing::warn!("Failed to flush trace spans: {}", error);
+    }
 
-    Ok(())
+    result
 }
@@ -1,16 +1,43 @@
-use sha2::{Digest, Sha256};
+use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
 
 use crate::{
-    dal::account::{fetch_account_by_email, fetch_account_by_email_and_password, insert_account},
+    config::Config,
+    dal::{
+        account::{
+            Account, AccountRole, fetch_account_by_email, insert_account,
+            record_account_login_failure, reset_account_login_failures, update_account_password,
+            update_account_role,
+        },
+        login_failure_ip::{
+            fetch_login_failure_ip, mark_login_failure_ip_rejection_recorded,
+            record_login_failure_ip,
+        },
+    },
     services::{
-        model::account::{LoggedAccount, SavedAccount},
-        utils::error::AppError,
+        handler::{
+            auth_event::record_auth_event,
+            session::issue_session,
+            totp::{create_totp_challenge, is_totp_enabled},
+            verification::send_verification_mail,
+        },
+        mail::MailSender,
+        model::{
+            account::{LoginOutcome, SavedAccount},
+            auth_event::{AuthEventType, ClientOrigin},
+        },
+        utils::{
+            error::AppError,
+            password::{PasswordCheck, hash_password, verify_password},
+        },
     },
 };
 
 pub async fn register_user(
     pool: &PgPool,
+    config: &Config,
+    mailer: &dyn MailSender,
+    origin: &ClientOrigin,
     email: &str,
     password: &str,
 ) -> Result<SavedAccount, AppError> {
@@ -21,15 +48,37 @@ pub async fn register_user(
         })?;
 
     if let Some(account) = account {
-        return Err(AppError::EmailRegistered { account });
+        return Err(AppError::EmailRegistered {
+            account: Box::new(account),
+        });
     }
 
-    let password = &hash_password(password);
+    let password = &hash_password(config, password).await?;
 
-    if let Err(err) = insert_account(pool, email, password).await {
-        return Err(AppError::SqlxError {
-            msg: format!("Failed to insert: {}", err),
-        });
+    let account_id =
+        insert_account(pool, email, password)
+            .await
+            .map_err(|err| AppError::SqlxError {
+                msg: format!("Failed to insert: {}", err),
+            })?;
+
+    record_auth_event(
+        pool,
+        origin,
+        Some(account_id),
+        AuthEventType::Register,
+        None,
+    )
+    .await;
+
+    // The account exists at this point; a failed mail is recoverable through
+    // the resend endpoint, so it must not fail the registration.
+    if let Err(err) = send_verification_mail(pool, config, mailer, account_id, email).await {
+        tracing::error!(
+            "Failed to send verification mail to account {}: {:?}",
+            account_id,
+            err
+        );
     }
 
     Ok(SavedAccount {
@@ -39,31 +88,253 @@ pub async fn register_user(
 
 pub async fn login_user(
     pool: &PgPool,
+    config: &Config,
+    origin: &ClientOrigin,
     email: &str,
     password: &str,
-) -> Result<LoggedAccount, AppError> {
-    let password = hash_password(password);
-    let account = fetch_account_by_email_and_password(pool, email, &password)
+) -> Result<LoginOutcome, AppError> {
+    if let Err(err) = check_client_ip_allowed(pool, config, &origin.client_ip).await {
+        record_client_ip_rejection(pool, origin).await;
+        return Err(err);
+    }
+
+    let account = fetch_account_by_email(pool, email)
         .await
         .map_err(|err| AppError::SqlxError {
             msg: format!("Failed to query: {}", err),
         })?;
 
-    if let None = account {
+    let Some(account) = account else {
+        // Spend the same hashing cost as a real verification so response
+        // timing does not reveal whether the email is registered.
+        hash_password(config, password).await?;
+        record_login_failure(pool, config, None, &origin.client_ip).await?;
+        record_login_failure_event(pool, origin, None, "unknown account").await;
         return Err(AppError::InvalidCredentials {
             msg: String::from("Invalid Account"),
         });
+    };
+
+    // A locked account still costs the hashing work, so response timing does
+    // not tell whether the password was right, and counts against the IP.
+    if let Some(until) = account.locked_until
+        && until > Utc::now()
+    {
+        verify_password(config, password, &account.password).await?;
+        record_login_failure(pool, config, None, &origin.client_ip).await?;
+        record_login_failure_event(pool, origin, Some(account.id), "account locked").await;
+        return Err(AppError::AccountLocked { until });
     }
 
-    Ok(LoggedAccount {
-        email: account.unwrap().email,
-        session_id: String::from("test"),
-        session_expire_time: String::from("test"),
-    })
+    match verify_password(config, password, &account.password).await? {
+        PasswordCheck::Invalid => {
+            let locked_until =
+                record_login_failure(pool, config, Some(account.id), &origin.client_ip).await?;
+            record_login_failure_event(pool, origin, Some(account.id), "invalid password").await;
+            if let Some(until) = locked_until {
+                return Err(AppError::AccountLocked { until });
+            }
+            return Err(AppError::InvalidCredentials {
+                msg: String::from("Invalid Password"),
+            });
+        }
+        PasswordCheck::Valid { needs_rehash: true } => {
+            let upgraded = hash_password(config, password).await?;
+            update_account_password(pool, account.id, &upgraded)
+                .await
+                .map_err(|err| AppError::SqlxError {
+                    msg: format!("Failed to upgrade password hash: {}", err),
+                })?;
+            tracing::info!("Upgraded password hash for account {}", account.id);
+        }
+        PasswordCheck::Valid {
+            needs_rehash: false,
+        } => {}
+    }
+
+    reset_account_login_failures(pool, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to reset login failures: {}", err),
+        })?;
+
+    // Checked only after the password so the disabled state is not revealed
+    // to someone guessing credentials.
+    if account.disabled_at.is_some() {
+        record_login_failure_event(pool, origin, Some(account.id), "account disabled").await;
+        return Err(AppError::AccountDisabled {
+            msg: format!("Account {} is disabled", account.id),
+        });
+    }
+
+    if config.require_email_verification && account.verified_at.is_none() {
+        record_login_failure_event(pool, origin, Some(account.id), "email not verified").await;
+        return Err(AppError::EmailNotVerified {
+            msg: format!("Account {} has not verified its email", account.id),
+        });
+    }
+
+    start_session(pool, config, origin, &account, "password").await
+}
+
+/// Final step of every first-factor login: a session, or a TOTP challenge
+/// when the account has a second factor enabled. `method` names the first
+/// factor in the audit log.
+pub async fn start_session(
+    pool: &PgPool,
+    config: &Config,
+    origin: &ClientOrigin,
+    account: &Account,
+    method: &str,
+) -> Result<LoginOutcome, AppError> {
+    if is_totp_enabled(pool, account.id).await? {
+        let challenge = create_totp_challenge(pool, config, account.id).await?;
+        return Ok(LoginOutcome::TotpRequired(challenge));
+    }
+
+    let logged_account = issue_session(pool, config, account).await?;
+    record_auth_event(
+        pool,
+        origin,
+        Some(account.id),
+        AuthEventType::LoginSuccess,
+        Some(method),
+    )
+    .await;
+    Ok(LoginOutcome::LoggedIn(logged_account))
 }
 
-fn hash_password(password: &str) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(password);
-    format!("{:x}", hasher.finalize())
+pub async fn record_login_failure_event(
+    pool: &PgPool,
+    origin: &ClientOrigin,
+    account_id: Option<i32>,
+    reason: &str,
+) {
+    record_auth_event(
+        pool,
+        origin,
+        account_id,
+        AuthEventType::LoginFailure,
+        Some(reason),
+    )
+    .await;
+}
+
+/// Audits the first rejection of a blocked IP per window; the rest would only
+/// pile up rows nothing ever prunes.
+async fn record_client_ip_rejection(pool: &PgPool, origin: &ClientOrigin) {
+    match mark_login_failure_ip_rejection_recorded(pool, &origin.client_ip).await {
+        Ok(true) => {
+            record_login_failure_event(pool, origin, None, "too many attempts from client ip").await
+        }
+        Ok(false) => {}
+        Err(err) => tracing::error!("Failed to flag client ip rejection: {}", err),
+    }
+}
+
+pub async fn check_client_ip_allowed(
+    pool: &PgPool,
+    config: &Config,
+    client_ip: &str,
+) -> Result<(), AppError> {
+    let failure = fetch_login_failure_ip(pool, client_ip)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query login failures: {}", err),
+        })?;
+
+    let Some(failure) = failure else {
+        return Ok(());
+    };
+
+    let window_end = failure.window_start + Duration::seconds(config.login_ip_window_seconds);
+    if failure.failed_count >= config.login_ip_max_failed_attempts && window_end > Utc::now() {
+        return Err(AppError::TooManyAttempts {
+            msg: format!(
+                "{} failed logins from {} since {}",
+                failure.failed_count,
+                client_ip,
+                failure.window_start.to_rfc3339()
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Counts a failure against the client IP and, when known, the account.
+/// Returns the account lock expiry if this failure triggered a lockout.
+/// Only the account counters are reset by a successful login; the IP window
+/// expires on its own so one valid login cannot clear a stuffing run.
+pub async fn record_login_failure(
+    pool: &PgPool,
+    config: &Config,
+    account_id: Option<i32>,
+    client_ip: &str,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    record_login_failure_ip(pool, client_ip, config.login_ip_window_seconds)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to record login failure: {}", err),
+        })?;
+
+    let Some(account_id) = account_id else {
+        return Ok(None);
+    };
+
+    let locked_until = record_account_login_failure(
+        pool,
+        account_id,
+        config.login_max_failed_attempts,
+        config.login_lockout_base_seconds,
+        config.login_lockout_max_seconds,
+    )
+    .await
+    .map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to record login failure: {}", err),
+    })?;
+
+    if let Some(until) = locked_until {
+        tracing::warn!(
+            "Account {} locked until {} after repeated failed logins",
+            account_id,
+            until.to_rfc3339()
+        );
+    }
+
+    Ok(locked_until)
+}
+
+/// Gives an account a new role. Used from the command line, so it is looked
+/// up by email.
+pub async fn set_account_role(
+    pool: &PgPool,
+    email: &str,
+    role: AccountRole,
+) -> Result<(), AppError> {
+    let account = fetch_account_by_email(pool, email)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        return Err(AppError::AccountNotFound {
+            msg: format!("No account registered with {}", email),
+        });
+    };
+
+    update_account_role(pool, account.id, role)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to update role: {}", err),
+        })?;
+
+    tracing::info!(
+        "Account {} role changed from {:?} to {:?}",
+        account.id,
+        account.role,
+        role
+    );
+    Ok(())
 }
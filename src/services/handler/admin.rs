@@ -0,0 +1,190 @@
+use chrono::Utc;
+use sqlx::PgPool;
+
+use crate::{
+    config::Config,
+    dal::account::{
+        Account, AccountQuery, count_accounts, fetch_account_by_id, fetch_accounts_page,
+        update_account_disabled_at,
+    },
+    dal::session::fetch_active_sessions_by_account_id,
+    services::{
+        handler::{
+            auth_event::list_auth_events, password::request_password_reset,
+            session::revoke_all_sessions, totp::is_totp_enabled,
+        },
+        mail::MailSender,
+        model::{
+            admin::{AdminAccount, AdminAccountDetail, AdminAccountPage},
+            auth_event::{AuthEventPage, ClientOrigin},
+            session::RevokedSessions,
+        },
+        utils::error::AppError,
+    },
+};
+
+pub async fn list_accounts(
+    pool: &PgPool,
+    query: &AccountQuery<'_>,
+    page: i64,
+    page_size: i64,
+) -> Result<AdminAccountPage, AppError> {
+    let accounts = fetch_accounts_page(pool, query, page_size, (page - 1) * page_size)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query accounts: {}", err),
+        })?;
+
+    let total_count = count_accounts(pool, query)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to count accounts: {}", err),
+        })?;
+
+    Ok(AdminAccountPage {
+        accounts: accounts.iter().map(AdminAccount::from).collect(),
+        page,
+        page_size,
+        total_count,
+    })
+}
+
+pub async fn get_account(pool: &PgPool, account_id: i32) -> Result<AdminAccountDetail, AppError> {
+    let account = find_account(pool, account_id).await?;
+
+    let totp_enabled = is_totp_enabled(pool, account.id).await?;
+    let sessions = fetch_active_sessions_by_account_id(pool, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query sessions: {}", err),
+        })?;
+
+    Ok(AdminAccountDetail {
+        account: AdminAccount::from(&account),
+        totp_enabled,
+        active_session_count: sessions.len(),
+    })
+}
+
+pub async fn get_account_activity(
+    pool: &PgPool,
+    account_id: i32,
+    page: i64,
+    page_size: i64,
+) -> Result<AuthEventPage, AppError> {
+    let account = find_account(pool, account_id).await?;
+
+    list_auth_events(pool, account.id, page, page_size).await
+}
+
+/// Blocks the account from signing in, ends its sessions and revokes its API
+/// keys. An admin cannot disable their own account and lock themselves out.
+pub async fn disable_account(
+    pool: &PgPool,
+    origin: &ClientOrigin,
+    admin: &Account,
+    account_id: i32,
+) -> Result<RevokedSessions, AppError> {
+    if admin.id == account_id {
+        return Err(AppError::AdminAction {
+            msg: format!("Admin {} tried to disable their own account", admin.id),
+        });
+    }
+
+    let updated = update_account_disabled_at(pool, account_id, Some(Utc::now()))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to disable account: {}", err),
+        })?;
+
+    if !updated {
+        return Err(account_not_found(account_id));
+    }
+
+    let revoked_sessions = revoke_all_sessions(
+        pool,
+        origin,
+        account_id,
+        &format!("account disabled by admin {}", admin.id),
+    )
+    .await?;
+    tracing::info!("Admin {} disabled account {}", admin.id, account_id);
+    Ok(revoked_sessions)
+}
+
+pub async fn enable_account(
+    pool: &PgPool,
+    admin: &Account,
+    account_id: i32,
+) -> Result<(), AppError> {
+    let updated = update_account_disabled_at(pool, account_id, None)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to enable account: {}", err),
+        })?;
+
+    if !updated {
+        return Err(account_not_found(account_id));
+    }
+
+    tracing::info!("Admin {} enabled account {}", admin.id, account_id);
+    Ok(())
+}
+
+pub async fn force_logout(
+    pool: &PgPool,
+    origin: &ClientOrigin,
+    admin: &Account,
+    account_id: i32,
+) -> Result<RevokedSessions, AppError> {
+    find_account(pool, account_id).await?;
+
+    let revoked_sessions = revoke_all_sessions(
+        pool,
+        origin,
+        account_id,
+        &format!("signed out by admin {}", admin.id),
+    )
+    .await?;
+    tracing::info!(
+        "Admin {} signed out account {} ({} session(s))",
+        admin.id,
+        account_id,
+        revoked_sessions.revoked_count
+    );
+    Ok(revoked_sessions)
+}
+
+/// Mails the account the same reset link as the forgot-password flow.
+pub async fn trigger_password_reset(
+    pool: &PgPool,
+    config: &Config,
+    mailer: &dyn MailSender,
+    admin: &Account,
+    account_id: i32,
+) -> Result<(), AppError> {
+    let account = find_account(pool, account_id).await?;
+
+    request_password_reset(pool, config, mailer, &account.email).await?;
+    tracing::info!(
+        "Admin {} sent a password reset to account {}",
+        admin.id,
+        account_id
+    );
+    Ok(())
+}
+
+async fn find_account(pool: &PgPool, account_id: i32) -> Result<Account, AppError> {
+    fetch_account_by_id(pool, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query account: {}", err),
+        })?
+        .ok_or_else(|| account_not_found(account_id))
+}
+
+fn account_not_found(account_id: i32) -> AppError {
+    AppError::AccountNotFound {
+        msg: format!("Account {} does not exist", account_id),
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    dal::{
+        account::{Account, AccountRole, fetch_account_by_id},
+        api_key::{
+            ApiKey, NewApiKey, fetch_api_key_by_hash, fetch_api_keys_by_account_id, insert_api_key,
+            revoke_api_key, touch_api_key,
+        },
+    },
+    services::{
+        model::api_key::{ApiKeyScope, ApiKeySummary, CreatedApiKey},
+        utils::{
+            error::AppError,
+            token::{generate_token, hash_token},
+        },
+    },
+};
+
+/// Every API key starts with this, so the authentication layer can tell keys
+/// from session tokens and leaked keys are easy to grep for.
+pub const API_KEY_PREFIX: &str = "pnp_";
+
+/// Characters of the key kept in clear to identify it in listings.
+const KEY_PREFIX_LENGTH: usize = 12;
+
+pub async fn create_api_key(
+    pool: &PgPool,
+    account: &Account,
+    name: &str,
+    scopes: &[ApiKeyScope],
+    expires_in_days: Option<i64>,
+) -> Result<CreatedApiKey, AppError> {
+    if scopes.contains(&ApiKeyScope::Admin) && account.role < AccountRole::Admin {
+        return Err(AppError::InsufficientRole {
+            msg: format!("Account {} cannot mint admin API keys", account.id),
+        });
+    }
+
+    let key = format!("{}{}", API_KEY_PREFIX, generate_token());
+    let scopes: Vec<String> = scopes
+        .iter()
+        .map(|scope| scope.as_str().to_string())
+        .collect();
+
+    let api_key = insert_api_key(
+        pool,
+        &NewApiKey {
+            account_id: account.id,
+            name,
+            key_prefix: &key[..KEY_PREFIX_LENGTH],
+            key_hash: &hash_token(&key),
+            scopes: &scopes,
+            expiry_time: expires_in_days.map(|days| Utc::now() + Duration::days(days)),
+        },
+    )
+    .await
+    .map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to insert API key: {}", err),
+    })?;
+
+    tracing::info!("Account {} created API key {}", account.id, api_key.id);
+
+    Ok(CreatedApiKey {
+        key,
+        summary: ApiKeySummary::from(&api_key),
+    })
+}
+
+pub async fn list_api_keys(pool: &PgPool, account_id: i32) -> Result<Vec<ApiKeySummary>, AppError> {
+    let api_keys = fetch_api_keys_by_account_id(pool, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query API keys: {}", err),
+        })?;
+
+    Ok(api_keys.iter().map(ApiKeySummary::from).collect())
+}
+
+pub async fn revoke_own_api_key(
+    pool: &PgPool,
+    account_id: i32,
+    key_id: i32,
+) -> Result<(), AppError> {
+    let revoked = revoke_api_key(pool, key_id, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke API key: {}", err),
+        })?;
+
+    if !revoked {
+        return Err(AppError::ApiKeyNotFound {
+            msg: format!("Account {} has no active API key {}", account_id, key_id),
+        });
+    }
+
+    tracing::info!("Account {} revoked API key {}", account_id, key_id);
+    Ok(())
+}
+
+/// Resolves an API key to its account, the counterpart of
+/// `authenticate_session` for machine clients.
+pub async fn authenticate_api_key(pool: &PgPool, key: &str) -> Result<(Account, ApiKey), AppError> {
+    let api_key = fetch_api_key_by_hash(pool, &hash_token(key))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query API key: {}", err),
+        })?;
+
+    let Some(api_key) = api_key else {
+        return Err(AppError::InvalidApiKey {
+            msg: String::from("Unknown API key"),
+        });
+    };
+
+    if api_key.revoked_time.is_some() {
+        return Err(AppError::InvalidApiKey {
+            msg: format!("API key {} is revoked", api_key.id),
+        });
+    }
+
+    if let Some(expiry_time) = api_key.expiry_time
+        && expiry_time <= Utc::now()
+    {
+        return Err(AppError::InvalidApiKey {
+            msg: format!(
+                "API key {} expired at {}",
+                api_key.id,
+                expiry_time.to_rfc3339()
+            ),
+        });
+    }
+
+    let account = fetch_account_by_id(pool, api_key.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query account: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        return Err(AppError::InvalidApiKey {
+            msg: format!("API key owner {} no longer exists", api_key.account_id),
+        });
+    };
+
+    if account.disabled_at.is_some() {
+        return Err(AppError::InvalidApiKey {
+            msg: format!("API key owner {} is disabled", account.id),
+        });
+    }
+
+    // Usage tracking is best effort; it must not fail the request.
+    if let Err(err) = touch_api_key(pool, api_key.id, Duration::minutes(1)).await {
+        tracing::warn!("Failed to record use of API key {}: {}", api_key.id, err);
+    }
+
+    Ok((account, api_key))
+}
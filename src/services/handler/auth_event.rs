@@ -0,0 +1,83 @@
+use sqlx::PgPool;
+
+use crate::{
+    dal::auth_event::{NewAuthEvent, count_auth_events, fetch_auth_events_page, insert_auth_event},
+    services::{
+        metrics::METRICS,
+        model::auth_event::{AuthEventEntry, AuthEventPage, AuthEventType, ClientOrigin},
+        utils::error::AppError,
+    },
+};
+
+/// Longest user agent kept; anything past it is cut off.
+const MAXIMUM_USER_AGENT_LENGTH: usize = 512;
+
+/// Appends an event to the audit log and counts it in the metrics. A failed
+/// write is logged and otherwise ignored: the audit log must not turn a login
+/// or logout into an error.
+pub async fn record_auth_event(
+    pool: &PgPool,
+    origin: &ClientOrigin,
+    account_id: Option<i32>,
+    event_type: AuthEventType,
+    detail: Option<&str>,
+) {
+    METRICS.observe_auth_event(event_type, detail);
+
+    let user_agent = origin.user_agent.as_deref().map(|user_agent| {
+        match user_agent.char_indices().nth(MAXIMUM_USER_AGENT_LENGTH) {
+            Some((end, _)) => &user_agent[..end],
+            None => user_agent,
+        }
+    });
+
+    let event = NewAuthEvent {
+        account_id,
+        event_type: event_type.as_str(),
+        detail,
+        client_ip: &origin.client_ip,
+        user_agent,
+        request_id: &origin.request_id,
+    };
+
+    if let Err(err) = insert_auth_event(pool, &event).await {
+        tracing::error!(
+            "Failed to record {} event for account {:?}: {}",
+            event.event_type,
+            account_id,
+            err
+        );
+    }
+}
+
+pub async fn list_auth_events(
+    pool: &PgPool,
+    account_id: i32,
+    page: i64,
+    page_size: i64,
+) -> Result<AuthEventPage, AppError> {
+    let events = fetch_auth_events_page(
+        pool,
+        account_id,
+        page_size,
+        (page - 1).saturating_mul(page_size),
+    )
+    .await
+    .map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to query auth events: {}", err),
+    })?;
+
+    let total_count =
+        count_auth_events(pool, account_id)
+            .await
+            .map_err(|err| AppError::SqlxError {
+                msg: format!("Failed to count auth events: {}", err),
+            })?;
+
+    Ok(AuthEventPage {
+        events: events.into_iter().map(AuthEventEntry::from).collect(),
+        page,
+        page_size,
+        total_count,
+    })
+}
@@ -0,0 +1,154 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    config::Config,
+    dal::{
+        account::{
+            Account, cancel_account_deletion, delete_accounts_by_ids,
+            fetch_account_ids_due_for_deletion_for_update, schedule_account_deletion,
+        },
+        api_key::revoke_api_keys_by_account_id,
+        data_export::fetch_data_export_ids_by_account_ids,
+        refresh_token::revoke_refresh_tokens_by_account_id,
+        session::{delete_sessions_by_account_id, delete_sessions_by_account_ids},
+    },
+    services::{
+        handler::export::data_export_path, model::account::ScheduledDeletion,
+        utils::error::AppError,
+    },
+};
+
+/// Schedules the account for deletion after the grace window and signs it out
+/// everywhere, API keys included. Signing in again stays possible so the
+/// owner can cancel.
+pub async fn request_account_deletion(
+    pool: &PgPool,
+    config: &Config,
+    account: &Account,
+) -> Result<ScheduledDeletion, AppError> {
+    let delete_after = Utc::now() + Duration::seconds(config.account_deletion_grace_seconds);
+
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let scheduled = schedule_account_deletion(&mut *tx, account.id, delete_after)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to schedule account deletion: {}", err),
+        })?;
+
+    if !scheduled {
+        return Err(AppError::AccountDeletionState {
+            msg: format!("Account {} is already scheduled for deletion", account.id),
+        });
+    }
+
+    revoke_refresh_tokens_by_account_id(&mut *tx, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke refresh tokens: {}", err),
+        })?;
+
+    revoke_api_keys_by_account_id(&mut *tx, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke API keys: {}", err),
+        })?;
+
+    let revoked_count = delete_sessions_by_account_id(&mut *tx, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete sessions: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit account deletion: {}", err),
+    })?;
+
+    tracing::info!(
+        "Account {} scheduled for deletion after {}",
+        account.id,
+        delete_after.to_rfc3339()
+    );
+
+    Ok(ScheduledDeletion {
+        delete_after: delete_after.to_rfc3339(),
+        revoked_count,
+    })
+}
+
+pub async fn cancel_deletion(pool: &PgPool, account_id: i32) -> Result<(), AppError> {
+    let cancelled = cancel_account_deletion(pool, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to cancel account deletion: {}", err),
+        })?;
+
+    if !cancelled {
+        return Err(AppError::AccountDeletionState {
+            msg: format!("Account {} is not scheduled for deletion", account_id),
+        });
+    }
+
+    tracing::info!("Account {} cancelled its deletion", account_id);
+    Ok(())
+}
+
+/// Hard-deletes one batch of accounts whose grace window is over, together
+/// with everything they own, export archives on disk included. Returns how
+/// many accounts were deleted.
+pub async fn purge_deleted_accounts(pool: &PgPool, config: &Config) -> Result<u64, AppError> {
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let account_ids = fetch_account_ids_due_for_deletion_for_update(
+        &mut tx,
+        Utc::now(),
+        config.account_deletion_batch_size,
+    )
+    .await
+    .map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to query accounts due for deletion: {}", err),
+    })?;
+
+    if account_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let export_ids = fetch_data_export_ids_by_account_ids(&mut *tx, &account_ids)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query data exports: {}", err),
+        })?;
+
+    delete_sessions_by_account_ids(&mut *tx, &account_ids)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete sessions: {}", err),
+        })?;
+
+    let deleted_count = delete_accounts_by_ids(&mut *tx, &account_ids)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete accounts: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit account purge: {}", err),
+    })?;
+
+    for export_id in export_ids {
+        let path = data_export_path(config, &export_id);
+        if let Err(err) = tokio::fs::remove_file(&path).await
+            && err.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::error!("Failed to remove data export {}: {}", path.display(), err);
+        }
+    }
+
+    tracing::info!("Purged accounts {:?}", account_ids);
+    Ok(deleted_count)
+}
@@ -0,0 +1,373 @@
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use tokio::fs::File;
+use tokio_util::task::TaskTracker;
+use tracing::Instrument;
+
+use crate::{
+    config::Config,
+    dal::{
+        account::fetch_account_by_id,
+        account_identity::fetch_account_identities_by_account_id,
+        account_totp::fetch_account_totp,
+        auth_event::fetch_auth_events_by_account_id,
+        data_export::{
+            DATA_EXPORT_PENDING, DATA_EXPORT_READY, fail_stale_data_exports,
+            fetch_data_export_by_id, fetch_data_exports_by_account_id, fetch_latest_data_export,
+            insert_data_export, mark_data_export_failed, mark_data_export_ready,
+        },
+        email_verification_token::fetch_email_verification_tokens_by_account_id,
+        login_challenge::fetch_login_challenges_by_account_id,
+        login_link_token::fetch_login_link_tokens_by_account_id,
+        password_history::fetch_password_history_by_account_id,
+        password_reset_token::fetch_password_reset_tokens_by_account_id,
+        refresh_token::fetch_refresh_tokens_by_account_id,
+        session::fetch_sessions_by_account_id,
+        totp_recovery_code::fetch_totp_recovery_codes_by_account_id,
+    },
+    services::{
+        model::auth_event::AuthEventEntry,
+        model::export::{
+            DataExportArchive, DataExportJob, ExportedAccount, ExportedIdentity,
+            ExportedRefreshToken, ExportedToken, ExportedTotp,
+        },
+        utils::{
+            error::AppError,
+            token::{generate_token, hash_token},
+        },
+    },
+};
+
+/// Queues a personal data export and builds it on `tasks`. The returned job
+/// carries the only copy of the download link.
+pub async fn request_data_export(
+    pool: &PgPool,
+    config: &Arc<Config>,
+    tasks: &TaskTracker,
+    account_id: i32,
+) -> Result<DataExportJob, AppError> {
+    fail_stale_pending_exports(pool, config, account_id).await?;
+
+    let token = generate_token();
+    let export_id = hash_token(&token);
+
+    let inserted = insert_data_export(pool, &export_id, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to insert data export: {}", err),
+        })?;
+
+    if !inserted {
+        return Err(AppError::DataExportState {
+            msg: format!("Account {} already has an export in progress", account_id),
+        });
+    }
+
+    let task_pool = pool.clone();
+    let task_config = config.clone();
+    let task_export_id = export_id.clone();
+    tasks.spawn(
+        async move {
+            if let Err(err) =
+                build_data_export(&task_pool, &task_config, &task_export_id, account_id).await
+            {
+                tracing::error!(
+                    "Failed to build data export for account {}: {:?}",
+                    account_id,
+                    err
+                );
+                if let Err(err) = mark_data_export_failed(&task_pool, &task_export_id).await {
+                    tracing::error!("Failed to mark data export failed: {}", err);
+                }
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+
+    Ok(DataExportJob {
+        status: String::from(DATA_EXPORT_PENDING),
+        utc_create: Utc::now().to_rfc3339(),
+        ready_time: None,
+        expiry_time: None,
+        download_url: Some(format!(
+            "{}/account/export/download?token={}",
+            config.public_base_url.trim_end_matches('/'),
+            token
+        )),
+    })
+}
+
+pub async fn fetch_data_export_status(
+    pool: &PgPool,
+    config: &Config,
+    account_id: i32,
+) -> Result<DataExportJob, AppError> {
+    fail_stale_pending_exports(pool, config, account_id).await?;
+
+    let latest = fetch_latest_data_export(pool, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query data export: {}", err),
+        })?;
+
+    match latest {
+        Some(latest) => Ok(DataExportJob::from(&latest)),
+        None => Err(AppError::DataExportState {
+            msg: format!("Account {} has not requested an export", account_id),
+        }),
+    }
+}
+
+/// Builds run as detached tasks, so one lost to a crash or shutdown leaves
+/// its row pending. Past the build timeout such a row is failed, letting the
+/// account see the outcome and request a new export.
+async fn fail_stale_pending_exports(
+    pool: &PgPool,
+    config: &Config,
+    account_id: i32,
+) -> Result<(), AppError> {
+    let cutoff = Utc::now() - Duration::seconds(config.data_export_build_timeout_seconds);
+    let failed = fail_stale_data_exports(pool, account_id, cutoff)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to fail stale data exports: {}", err),
+        })?;
+
+    if failed > 0 {
+        tracing::warn!(
+            "Failed {} stale pending data export(s) of account {}",
+            failed,
+            account_id
+        );
+    }
+    Ok(())
+}
+
+/// Opens the archive behind a download token while its link is valid.
+pub async fn open_data_export(
+    pool: &PgPool,
+    config: &Config,
+    token: &str,
+) -> Result<File, AppError> {
+    let export = fetch_data_export_by_id(pool, &hash_token(token))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query data export: {}", err),
+        })?;
+
+    let Some(export) = export else {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Unknown data export token"),
+        });
+    };
+
+    if export.status != DATA_EXPORT_READY {
+        return Err(AppError::InvalidToken {
+            msg: format!("Data export is {}", export.status),
+        });
+    }
+
+    if export.expiry_time.is_none_or(|expiry| expiry <= Utc::now()) {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Data export link expired"),
+        });
+    }
+
+    File::open(data_export_path(config, &export.id))
+        .await
+        .map_err(|err| AppError::FileError {
+            msg: format!("Failed to open data export archive: {}", err),
+        })
+}
+
+pub fn data_export_path(config: &Config, export_id: &str) -> PathBuf {
+    config.data_export_dir.join(format!("{}.json", export_id))
+}
+
+async fn build_data_export(
+    pool: &PgPool,
+    config: &Config,
+    export_id: &str,
+    account_id: i32,
+) -> Result<(), AppError> {
+    let archive = collect_account_data(pool, account_id).await?;
+    let bytes = serde_json::to_vec_pretty(&archive).map_err(|err| AppError::FileError {
+        msg: format!("Failed to serialize data export: {}", err),
+    })?;
+
+    // Written under a temporary name so a download never sees a partial file.
+    let path = data_export_path(config, export_id);
+    let partial_path = path.with_extension("json.part");
+    tokio::fs::create_dir_all(&config.data_export_dir)
+        .await
+        .map_err(|err| AppError::FileError {
+            msg: format!("Failed to create data export directory: {}", err),
+        })?;
+    tokio::fs::write(&partial_path, bytes)
+        .await
+        .map_err(|err| AppError::FileError {
+            msg: format!("Failed to write data export archive: {}", err),
+        })?;
+    tokio::fs::rename(&partial_path, &path)
+        .await
+        .map_err(|err| AppError::FileError {
+            msg: format!("Failed to move data export archive: {}", err),
+        })?;
+
+    let expiry_time = Utc::now() + Duration::seconds(config.data_export_ttl_seconds);
+    mark_data_export_ready(pool, export_id, expiry_time)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to mark data export ready: {}", err),
+        })?;
+
+    tracing::info!("Data export ready for account {}", account_id);
+    Ok(())
+}
+
+async fn collect_account_data(
+    pool: &PgPool,
+    account_id: i32,
+) -> Result<DataExportArchive, AppError> {
+    let to_sqlx_error = |err: sqlx::Error| AppError::SqlxError {
+        msg: format!("Failed to collect account data: {}", err),
+    };
+
+    let account = fetch_account_by_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?
+        .ok_or_else(|| AppError::DataExportState {
+            msg: format!("Account {} no longer exists", account_id),
+        })?;
+
+    let sessions = fetch_sessions_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let refresh_tokens = fetch_refresh_tokens_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let password_reset_tokens = fetch_password_reset_tokens_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let email_verification_tokens = fetch_email_verification_tokens_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let login_challenges = fetch_login_challenges_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let login_link_tokens = fetch_login_link_tokens_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let totp = fetch_account_totp(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let totp_recovery_codes = fetch_totp_recovery_codes_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let password_history = fetch_password_history_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let identities = fetch_account_identities_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let auth_events = fetch_auth_events_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    let data_exports = fetch_data_exports_by_account_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+
+    Ok(DataExportArchive {
+        generated_at: Utc::now().to_rfc3339(),
+        account: ExportedAccount {
+            id: account.id,
+            email: account.email,
+            utc_create: account.utc_create.to_rfc3339(),
+            utc_modified: account.utc_modified.to_rfc3339(),
+            verified_at: rfc3339(account.verified_at),
+            failed_login_count: account.failed_login_count,
+            lockout_count: account.lockout_count,
+            locked_until: rfc3339(account.locked_until),
+            delete_after: rfc3339(account.delete_after),
+        },
+        sessions: sessions
+            .iter()
+            .map(|session| ExportedToken {
+                utc_create: session.utc_create.to_rfc3339(),
+                expiry_time: Some(session.expiry_time.to_rfc3339()),
+                used_time: None,
+            })
+            .collect(),
+        refresh_tokens: refresh_tokens
+            .iter()
+            .map(|token| ExportedRefreshToken {
+                utc_create: token.utc_create.to_rfc3339(),
+                expiry_time: token.expiry_time.to_rfc3339(),
+                rotated_time: rfc3339(token.rotated_time),
+                revoked_time: rfc3339(token.revoked_time),
+            })
+            .collect(),
+        password_reset_tokens: password_reset_tokens
+            .iter()
+            .map(|token| exported_token(token.utc_create, token.expiry_time, token.used_time))
+            .collect(),
+        email_verification_tokens: email_verification_tokens
+            .iter()
+            .map(|token| exported_token(token.utc_create, token.expiry_time, token.used_time))
+            .collect(),
+        login_challenges: login_challenges
+            .iter()
+            .map(|token| exported_token(token.utc_create, token.expiry_time, token.used_time))
+            .collect(),
+        login_link_tokens: login_link_tokens
+            .iter()
+            .map(|token| exported_token(token.utc_create, token.expiry_time, token.used_time))
+            .collect(),
+        totp: totp.map(|totp| ExportedTotp {
+            utc_create: totp.utc_create.to_rfc3339(),
+            enabled_at: rfc3339(totp.enabled_at),
+        }),
+        totp_recovery_codes: totp_recovery_codes
+            .iter()
+            .map(|code| ExportedToken {
+                utc_create: code.utc_create.to_rfc3339(),
+                expiry_time: None,
+                used_time: rfc3339(code.used_time),
+            })
+            .collect(),
+        password_changes: password_history
+            .iter()
+            .map(|entry| entry.utc_create.to_rfc3339())
+            .collect(),
+        linked_identities: identities
+            .into_iter()
+            .map(|identity| ExportedIdentity {
+                provider: identity.provider,
+                subject: identity.subject,
+                email: identity.email,
+                utc_create: identity.utc_create.to_rfc3339(),
+                last_login_time: rfc3339(identity.last_login_time),
+            })
+            .collect(),
+        auth_events: auth_events.into_iter().map(AuthEventEntry::from).collect(),
+        data_exports: data_exports.iter().map(DataExportJob::from).collect(),
+    })
+}
+
+fn exported_token(
+    utc_create: DateTime<Utc>,
+    expiry_time: DateTime<Utc>,
+    used_time: Option<DateTime<Utc>>,
+) -> ExportedToken {
+    ExportedToken {
+        utc_create: utc_create.to_rfc3339(),
+        expiry_time: Some(expiry_time.to_rfc3339()),
+        used_time: rfc3339(used_time),
+    }
+}
+
+fn rfc3339(time: Option<DateTime<Utc>>) -> Option<String> {
+    time.map(|time| time.to_rfc3339())
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::time::{Instant, timeout};
+
+use crate::{
+    config::Config,
+    dal::migration::{MIGRATOR, fetch_applied_migration_versions, ping},
+    services::model::health::{
+        DatabaseCheck, HealthStatus, MigrationCheck, PoolCheck, ReadinessReport,
+    },
+};
+
+/// Whether this replica should receive traffic: the database answers within
+/// the timeout, it has every migration this binary ships, the pool has a
+/// connection to spare and the server is not shutting down.
+pub async fn check_readiness(
+    pool: &PgPool,
+    config: &Config,
+    shutting_down: bool,
+) -> ReadinessReport {
+    let check_timeout = Duration::from_secs(config.health_check_timeout_seconds);
+
+    let pool_check = check_pool(pool);
+    let (database, migrations) = tokio::join!(
+        check_database(pool, check_timeout),
+        check_migrations(pool, check_timeout)
+    );
+
+    let status = if !shutting_down
+        && database.status == HealthStatus::Up
+        && migrations.status == HealthStatus::Up
+        && pool_check.status == HealthStatus::Up
+    {
+        HealthStatus::Up
+    } else {
+        HealthStatus::Down
+    };
+
+    ReadinessReport {
+        status,
+        shutting_down,
+        database,
+        migrations,
+        pool: pool_check,
+    }
+}
+
+async fn check_database(pool: &PgPool, check_timeout: Duration) -> DatabaseCheck {
+    let start_time = Instant::now();
+    let error = match timeout(check_timeout, ping(pool)).await {
+        Ok(Ok(())) => None,
+        Ok(Err(err)) => Some(err.to_string()),
+        Err(_) => Some(format!("no answer within {} s", check_timeout.as_secs())),
+    };
+
+    DatabaseCheck {
+        status: if error.is_none() {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        },
+        latency_ms: start_time.elapsed().as_millis(),
+        error,
+    }
+}
+
+async fn check_migrations(pool: &PgPool, check_timeout: Duration) -> MigrationCheck {
+    let applied = match timeout(check_timeout, fetch_applied_migration_versions(pool)).await {
+        Ok(Ok(applied)) => applied,
+        Ok(Err(err)) => return migration_check_failed(err.to_string()),
+        Err(_) => {
+            return migration_check_failed(format!(
+                "no answer within {} s",
+                check_timeout.as_secs()
+            ));
+        }
+    };
+
+    let pending: Vec<i64> = MIGRATOR
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| migration.version)
+        .filter(|version| !applied.contains(version))
+        .collect();
+
+    MigrationCheck {
+        status: if pending.is_empty() {
+            HealthStatus::Up
+        } else {
+            HealthStatus::Down
+        },
+        pending,
+        error: None,
+    }
+}
+
+fn migration_check_failed(error: String) -> MigrationCheck {
+    MigrationCheck {
+        status: HealthStatus::Down,
+        pending: Vec::new(),
+        error: Some(error),
+    }
+}
+
+/// Saturated means every connection the pool may open is checked out.
+fn check_pool(pool: &PgPool) -> PoolCheck {
+    let size = pool.size();
+    let idle = pool.num_idle();
+    let max_size = pool.options().get_max_connections();
+
+    PoolCheck {
+        status: if size >= max_size && idle == 0 {
+            HealthStatus::Down
+        } else {
+            HealthStatus::Up
+        },
+        size,
+        idle,
+        max_size,
+    }
+}
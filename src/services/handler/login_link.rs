@@ -0,0 +1,185 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    config::Config,
+    dal::{
+        account::{fetch_account_by_email, fetch_account_by_id, mark_account_verified},
+        login_link_token::{
+            fetch_latest_login_link_token, fetch_login_link_token_for_update,
+            insert_login_link_token, mark_login_link_tokens_used,
+        },
+    },
+    services::{
+        handler::account::{check_client_ip_allowed, record_login_failure_event, start_session},
+        mail::{MailMessage, MailSender},
+        model::{account::LoginOutcome, auth_event::ClientOrigin},
+        utils::{
+            error::AppError,
+            token::{generate_token, hash_token},
+        },
+    },
+};
+
+pub fn ensure_login_link_enabled(config: &Config) -> Result<(), AppError> {
+    if !config.login_link_enabled {
+        return Err(AppError::LoginLinkDisabled {
+            msg: String::from("Sign-in links are disabled"),
+        });
+    }
+    Ok(())
+}
+
+/// Mails a sign-in link when the email belongs to an active account. Unknown
+/// emails succeed silently so the caller cannot tell the cases apart, and so
+/// do requests arriving sooner than the resend interval after the last link.
+pub async fn request_login_link(
+    pool: &PgPool,
+    config: &Config,
+    mailer: &dyn MailSender,
+    email: &str,
+) -> Result<(), AppError> {
+    ensure_login_link_enabled(config)?;
+
+    let account = fetch_account_by_email(pool, email)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        tracing::info!("Sign-in link requested for unknown email");
+        return Ok(());
+    };
+
+    if account.disabled_at.is_some() {
+        tracing::info!("Sign-in link requested for disabled account {}", account.id);
+        return Ok(());
+    }
+
+    let latest = fetch_latest_login_link_token(pool, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query sign-in link token: {}", err),
+        })?;
+
+    let throttle = Duration::seconds(config.login_link_resend_interval_seconds);
+    if let Some(latest) = latest
+        && latest.utc_create + throttle > Utc::now()
+    {
+        tracing::info!("Sign-in link throttled for account {}", account.id);
+        return Ok(());
+    }
+
+    let token = generate_token();
+    let expiry_time = Utc::now() + Duration::seconds(config.login_link_ttl_seconds);
+
+    insert_login_link_token(pool, &hash_token(&token), account.id, expiry_time)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to insert sign-in link token: {}", err),
+        })?;
+
+    let message = MailMessage {
+        to: account.email,
+        subject: String::from("Your Plug and Plant sign-in link"),
+        body: format!(
+            "Use this link before {} to sign in to Plug and Plant:\n\
+             {}/login-link?token={}\n\n\
+             The link works once. If you didn't ask for it, you can ignore this mail.",
+            expiry_time.to_rfc3339(),
+            config.frontend_base_url.trim_end_matches('/'),
+            token
+        ),
+    };
+
+    mailer.send(&message).await
+}
+
+/// Consumes a sign-in link and logs the account in exactly like a password
+/// login would, throttled client IPs and locked accounts included. Opening
+/// the link also proves the email belongs to the account.
+pub async fn login_with_link(
+    pool: &PgPool,
+    config: &Config,
+    origin: &ClientOrigin,
+    token: &str,
+) -> Result<LoginOutcome, AppError> {
+    ensure_login_link_enabled(config)?;
+    check_client_ip_allowed(pool, config, &origin.client_ip).await?;
+
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let link_token = fetch_login_link_token_for_update(&mut tx, &hash_token(token))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query sign-in link token: {}", err),
+        })?;
+
+    let Some(link_token) = link_token else {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Unknown sign-in link token"),
+        });
+    };
+
+    if link_token.used_time.is_some() {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Sign-in link token already used"),
+        });
+    }
+
+    if link_token.expiry_time <= Utc::now() {
+        return Err(AppError::InvalidToken {
+            msg: format!(
+                "Sign-in link token expired at {}",
+                link_token.expiry_time.to_rfc3339()
+            ),
+        });
+    }
+
+    let mut account = fetch_account_by_id(pool, link_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query: {}", err),
+        })?
+        .ok_or_else(|| AppError::InvalidToken {
+            msg: format!("Account {} no longer exists", link_token.account_id),
+        })?;
+
+    // A disabled or locked account keeps its link unused and its email
+    // unverified.
+    if account.disabled_at.is_some() {
+        return Err(AppError::AccountDisabled {
+            msg: format!("Account {} is disabled", account.id),
+        });
+    }
+
+    if let Some(until) = account.locked_until
+        && until > Utc::now()
+    {
+        record_login_failure_event(pool, origin, Some(account.id), "account locked").await;
+        return Err(AppError::AccountLocked { until });
+    }
+
+    mark_login_link_tokens_used(&mut *tx, link_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to mark sign-in link token used: {}", err),
+        })?;
+
+    mark_account_verified(&mut *tx, link_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to mark account verified: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit transaction: {}", err),
+    })?;
+
+    account.verified_at.get_or_insert_with(Utc::now);
+
+    start_session(pool, config, origin, &account, "link").await
+}
@@ -1 +1,14 @@
 pub mod account;
+pub mod admin;
+pub mod api_key;
+pub mod auth_event;
+pub mod deletion;
+pub mod export;
+pub mod health;
+pub mod login_link;
+pub mod oidc;
+pub mod password;
+pub mod purge;
+pub mod session;
+pub mod totp;
+pub mod verification;
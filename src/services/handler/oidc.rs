@@ -0,0 +1,385 @@
+use chrono::{Duration, Utc};
+use sqlx::{PgConnection, PgPool};
+
+use crate::{
+    config::Config,
+    dal::{
+        account::{
+            Account, fetch_account_by_email, fetch_account_by_id, insert_account,
+            mark_account_verified, update_account_password,
+        },
+        account_identity::{
+            AccountIdentity, fetch_account_identity, insert_account_identity,
+            touch_account_identity,
+        },
+        account_totp::delete_account_totp,
+        api_key::revoke_api_keys_by_account_id,
+        oidc_login_state::{NewOidcLoginState, insert_oidc_login_state, take_oidc_login_state},
+        refresh_token::revoke_refresh_tokens_by_account_id,
+        session::delete_sessions_by_account_id,
+    },
+    services::{
+        handler::{account::start_session, auth_event::record_auth_event},
+        model::{
+            account::LoginOutcome,
+            auth_event::{AuthEventType, ClientOrigin},
+            oidc::{OidcAuthorization, OidcCallback},
+        },
+        oidc::{IdTokenClaims, OidcClient, OidcProviderConfig, pkce_challenge},
+        utils::{
+            email::normalize_email,
+            error::AppError,
+            password::hash_password,
+            token::{constant_time_eq, generate_token, hash_token},
+        },
+    },
+};
+
+/// Starts an authorization-code login with PKCE and returns the provider URL
+/// the browser has to visit.
+pub async fn start_oidc_login(
+    pool: &PgPool,
+    config: &Config,
+    oidc: &OidcClient,
+    provider_name: &str,
+) -> Result<OidcAuthorization, AppError> {
+    let provider = find_provider(config, provider_name)?;
+    let metadata = oidc.metadata(provider).await?;
+
+    let state = generate_token();
+    let nonce = generate_token();
+    let code_verifier = generate_token();
+    let expiry_time = Utc::now() + Duration::seconds(config.oidc_state_ttl_seconds);
+
+    let mut authorization_url =
+        url::Url::parse(&metadata.authorization_endpoint).map_err(|err| {
+            AppError::OidcProvider {
+                msg: format!(
+                    "Invalid authorization endpoint of {}: {}",
+                    provider.name, err
+                ),
+            }
+        })?;
+    authorization_url
+        .query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", &redirect_uri(config, provider))
+        .append_pair("scope", &provider.scopes)
+        .append_pair("state", &state)
+        .append_pair("nonce", &nonce)
+        .append_pair("code_challenge", &pkce_challenge(&code_verifier))
+        .append_pair("code_challenge_method", "S256");
+
+    insert_oidc_login_state(
+        pool,
+        &NewOidcLoginState {
+            id: &hash_token(&state),
+            provider: &provider.name,
+            code_verifier: &code_verifier,
+            nonce: &nonce,
+            expiry_time,
+        },
+    )
+    .await
+    .map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to insert OIDC login state: {}", err),
+    })?;
+
+    Ok(OidcAuthorization {
+        authorization_url: authorization_url.into(),
+        state,
+        state_expire_time: expiry_time.to_rfc3339(),
+    })
+}
+
+/// Finishes the login the provider redirected back from. The state must match
+/// the one the browser kept when the login started, so a callback URL planted
+/// by someone else cannot sign the browser into their account. The account is
+/// found through a linked identity, else linked by verified email, else
+/// created when signup is allowed; the result is the same as a password login.
+pub async fn complete_oidc_login(
+    pool: &PgPool,
+    config: &Config,
+    oidc: &OidcClient,
+    provider_name: &str,
+    origin: &ClientOrigin,
+    callback: &OidcCallback<'_>,
+) -> Result<LoginOutcome, AppError> {
+    let provider = find_provider(config, provider_name)?;
+
+    let same_browser = callback
+        .browser_state
+        .is_some_and(|value| constant_time_eq(value.as_bytes(), callback.state.as_bytes()));
+    if !same_browser {
+        return Err(AppError::InvalidOidcLogin {
+            msg: String::from("OIDC state does not match the browser that started the login"),
+        });
+    }
+
+    let login_state = take_oidc_login_state(pool, &hash_token(callback.state), &provider.name)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to take OIDC login state: {}", err),
+        })?
+        .ok_or_else(|| AppError::InvalidOidcLogin {
+            msg: String::from("Unknown or already used OIDC state"),
+        })?;
+
+    if login_state.expiry_time <= Utc::now() {
+        return Err(AppError::InvalidOidcLogin {
+            msg: String::from("OIDC state expired"),
+        });
+    }
+
+    let id_token = oidc
+        .exchange_code(
+            provider,
+            &redirect_uri(config, provider),
+            callback.code,
+            &login_state.code_verifier,
+        )
+        .await?;
+    let claims = oidc
+        .verify_id_token(provider, &id_token, &login_state.nonce)
+        .await?;
+
+    let account = resolve_account(pool, config, origin, provider, &claims).await?;
+
+    if account.disabled_at.is_some() {
+        return Err(AppError::AccountDisabled {
+            msg: format!("Account {} is disabled", account.id),
+        });
+    }
+
+    start_session(
+        pool,
+        config,
+        origin,
+        &account,
+        &format!("oidc:{}", provider.name),
+    )
+    .await
+}
+
+/// Resets an unverified account to a state only the email owner can use: a
+/// password nobody knows, no sessions, no API keys and no authenticator.
+async fn reclaim_unverified_account(
+    conn: &mut PgConnection,
+    config: &Config,
+    account_id: i32,
+) -> Result<(), AppError> {
+    let to_sqlx_error = |err: sqlx::Error| AppError::SqlxError {
+        msg: format!("Failed to reclaim account {}: {}", account_id, err),
+    };
+
+    let password = hash_password(config, &generate_token()).await?;
+    update_account_password(&mut *conn, account_id, &password)
+        .await
+        .map_err(to_sqlx_error)?;
+    revoke_refresh_tokens_by_account_id(&mut *conn, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    delete_sessions_by_account_id(&mut *conn, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    revoke_api_keys_by_account_id(&mut *conn, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    delete_account_totp(&mut *conn, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+    Ok(())
+}
+
+fn find_provider<'a>(
+    config: &'a Config,
+    provider_name: &str,
+) -> Result<&'a OidcProviderConfig, AppError> {
+    config
+        .oidc_providers
+        .find(provider_name)
+        .ok_or_else(|| AppError::OidcProviderNotFound {
+            msg: format!("OIDC provider {} is not configured", provider_name),
+        })
+}
+
+fn redirect_uri(config: &Config, provider: &OidcProviderConfig) -> String {
+    provider.redirect_uri.clone().unwrap_or_else(|| {
+        format!(
+            "{}/account/oidc/{}/callback",
+            config.public_base_url.trim_end_matches('/'),
+            provider.name
+        )
+    })
+}
+
+async fn resolve_account(
+    pool: &PgPool,
+    config: &Config,
+    origin: &ClientOrigin,
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+) -> Result<Account, AppError> {
+    let to_sqlx_error = |err: sqlx::Error| AppError::SqlxError {
+        msg: format!("Failed to resolve OIDC account: {}", err),
+    };
+
+    let identity = fetch_account_identity(pool, &provider.name, &claims.sub)
+        .await
+        .map_err(to_sqlx_error)?;
+
+    if let Some(identity) = identity {
+        return identity_account(pool, &identity, claims).await;
+    }
+
+    // Linking by email is only safe when the provider vouches for it.
+    let Some(email) = claims
+        .email
+        .as_deref()
+        .filter(|_| claims.email_verified)
+        .map(normalize_email)
+    else {
+        return Err(AppError::EmailNotVerified {
+            msg: format!(
+                "{} did not assert a verified email for subject {}",
+                provider.name, claims.sub
+            ),
+        });
+    };
+
+    let existing = fetch_account_by_email(pool, &email)
+        .await
+        .map_err(to_sqlx_error)?;
+
+    let mut tx = pool.begin().await.map_err(to_sqlx_error)?;
+
+    let mut created = false;
+    let account_id = match existing {
+        Some(account) if account.verified_at.is_some() => account.id,
+        Some(account) => {
+            // Whoever registered this unverified account never proved they
+            // own the email; the provider just did. Drop every credential
+            // they may have left behind so it cannot outlive the link.
+            reclaim_unverified_account(&mut tx, config, account.id).await?;
+            tracing::warn!(
+                "Reclaimed unverified account {} on {} login",
+                account.id,
+                provider.name
+            );
+            account.id
+        }
+        None if config.oidc_allow_signup => {
+            // Nobody knows this password; the account can set a real one
+            // through the password reset flow.
+            let password = hash_password(config, &generate_token()).await?;
+            let account_id = match insert_account(&mut *tx, &email, &password).await {
+                Ok(account_id) => account_id,
+                Err(err) if is_unique_violation(&err) => {
+                    tx.rollback().await.map_err(to_sqlx_error)?;
+                    return linked_concurrently(pool, provider, claims).await;
+                }
+                Err(err) => return Err(to_sqlx_error(err)),
+            };
+            tracing::info!(
+                "Created account {} from {} login",
+                account_id,
+                provider.name
+            );
+            created = true;
+            account_id
+        }
+        None => {
+            return Err(AppError::AccountNotFound {
+                msg: format!("No account for {} and signup is disabled", email),
+            });
+        }
+    };
+
+    let inserted =
+        insert_account_identity(&mut *tx, account_id, &provider.name, &claims.sub, &email)
+            .await
+            .map_err(to_sqlx_error)?;
+    if !inserted {
+        tx.rollback().await.map_err(to_sqlx_error)?;
+        return linked_concurrently(pool, provider, claims).await;
+    }
+    mark_account_verified(&mut *tx, account_id)
+        .await
+        .map_err(to_sqlx_error)?;
+
+    tx.commit().await.map_err(to_sqlx_error)?;
+
+    if created {
+        record_auth_event(
+            pool,
+            origin,
+            Some(account_id),
+            AuthEventType::Register,
+            Some(&format!("oidc:{}", provider.name)),
+        )
+        .await;
+    }
+
+    tracing::info!(
+        "Linked {} subject {} to account {}",
+        provider.name,
+        claims.sub,
+        account_id
+    );
+
+    fetch_account_by_id(pool, account_id)
+        .await
+        .map_err(to_sqlx_error)?
+        .ok_or_else(|| AppError::AccountNotFound {
+            msg: format!("Account {} no longer exists", account_id),
+        })
+}
+
+async fn identity_account(
+    pool: &PgPool,
+    identity: &AccountIdentity,
+    claims: &IdTokenClaims,
+) -> Result<Account, AppError> {
+    let to_sqlx_error = |err: sqlx::Error| AppError::SqlxError {
+        msg: format!("Failed to resolve OIDC account: {}", err),
+    };
+
+    let email = claims.email.as_deref().unwrap_or(&identity.email);
+    touch_account_identity(pool, identity.id, email)
+        .await
+        .map_err(to_sqlx_error)?;
+
+    fetch_account_by_id(pool, identity.account_id)
+        .await
+        .map_err(to_sqlx_error)?
+        .ok_or_else(|| AppError::AccountNotFound {
+            msg: format!("Account {} no longer exists", identity.account_id),
+        })
+}
+
+/// A concurrent first login of the same subject linked it first; its
+/// committed identity is used instead of failing this login.
+async fn linked_concurrently(
+    pool: &PgPool,
+    provider: &OidcProviderConfig,
+    claims: &IdTokenClaims,
+) -> Result<Account, AppError> {
+    let identity = fetch_account_identity(pool, &provider.name, &claims.sub)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to resolve OIDC account: {}", err),
+        })?
+        .ok_or_else(|| AppError::SqlxError {
+            msg: format!(
+                "{} subject {} conflicted with an account it is not linked to",
+                provider.name, claims.sub
+            ),
+        })?;
+    identity_account(pool, &identity, claims).await
+}
+
+fn is_unique_violation(err: &sqlx::Error) -> bool {
+    err.as_database_error()
+        .is_some_and(|err| err.is_unique_violation())
+}
@@ -0,0 +1,339 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    config::Config,
+    dal::{
+        account::{Account, fetch_account_by_email, update_account_password},
+        api_key::revoke_api_keys_by_account_id,
+        password_history::{
+            archive_account_password, fetch_recent_password_history, trim_password_history,
+        },
+        password_reset_token::{
+            fetch_latest_password_reset_token, fetch_password_reset_token_for_update,
+            insert_password_reset_token, mark_password_reset_tokens_used,
+        },
+        refresh_token::{
+            revoke_other_refresh_tokens_by_account_id, revoke_refresh_tokens_by_account_id,
+        },
+        session::{delete_other_sessions_by_account_id, delete_sessions_by_account_id},
+    },
+    services::{
+        handler::{
+            account::{record_login_failure, record_login_failure_event},
+            auth_event::record_auth_event,
+        },
+        mail::{MailMessage, MailSender},
+        model::{
+            auth_event::{AuthEventType, ClientOrigin},
+            session::RevokedSessions,
+        },
+        utils::{
+            error::AppError,
+            password::{PasswordCheck, hash_password, verify_password},
+            token::{generate_token, hash_token},
+        },
+    },
+};
+
+/// Mails a reset link when the email belongs to an account that was not
+/// mailed one less than the configured interval ago. Unknown and throttled
+/// emails succeed silently so the caller cannot tell the cases apart.
+pub async fn request_password_reset(
+    pool: &PgPool,
+    config: &Config,
+    mailer: &dyn MailSender,
+    email: &str,
+) -> Result<(), AppError> {
+    let account = fetch_account_by_email(pool, email)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        tracing::info!("Password reset requested for unknown email");
+        return Ok(());
+    };
+
+    let latest = fetch_latest_password_reset_token(pool, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query password reset token: {}", err),
+        })?;
+
+    let throttle = Duration::seconds(config.password_reset_resend_interval_seconds);
+    if let Some(latest) = latest
+        && latest.utc_create + throttle > Utc::now()
+    {
+        tracing::info!("Password reset throttled for account {}", account.id);
+        return Ok(());
+    }
+
+    let token = generate_token();
+    let expiry_time = Utc::now() + Duration::seconds(config.password_reset_ttl_seconds);
+
+    insert_password_reset_token(pool, &hash_token(&token), account.id, expiry_time)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to insert password reset token: {}", err),
+        })?;
+
+    let message = MailMessage {
+        to: account.email,
+        subject: String::from("Reset your Plug and Plant password"),
+        body: format!(
+            "Someone asked to reset the password of your Plug and Plant account.\n\n\
+             Use this link before {} to choose a new one:\n{}/reset-password?token={}\n\n\
+             If it wasn't you, you can ignore this mail.",
+            expiry_time.to_rfc3339(),
+            config.frontend_base_url.trim_end_matches('/'),
+            token
+        ),
+    };
+
+    mailer.send(&message).await
+}
+
+/// Consumes a reset token, sets the new password and signs the account out
+/// everywhere, revoking its API keys too.
+pub async fn reset_password(
+    pool: &PgPool,
+    config: &Config,
+    origin: &ClientOrigin,
+    token: &str,
+    new_password: &str,
+) -> Result<RevokedSessions, AppError> {
+    let password = hash_password(config, new_password).await?;
+
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let reset_token = fetch_password_reset_token_for_update(&mut tx, &hash_token(token))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query password reset token: {}", err),
+        })?;
+
+    let Some(reset_token) = reset_token else {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Unknown password reset token"),
+        });
+    };
+
+    if reset_token.used_time.is_some() {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Password reset token already used"),
+        });
+    }
+
+    if reset_token.expiry_time <= Utc::now() {
+        return Err(AppError::InvalidToken {
+            msg: format!(
+                "Password reset token expired at {}",
+                reset_token.expiry_time.to_rfc3339()
+            ),
+        });
+    }
+
+    archive_account_password(&mut *tx, reset_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to archive password: {}", err),
+        })?;
+
+    trim_password_history(&mut *tx, reset_token.account_id, history_depth(config))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to trim password history: {}", err),
+        })?;
+
+    update_account_password(&mut *tx, reset_token.account_id, &password)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to update password: {}", err),
+        })?;
+
+    mark_password_reset_tokens_used(&mut *tx, reset_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to mark password reset token used: {}", err),
+        })?;
+
+    revoke_refresh_tokens_by_account_id(&mut *tx, reset_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke refresh tokens: {}", err),
+        })?;
+
+    let revoked_count = delete_sessions_by_account_id(&mut *tx, reset_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete sessions: {}", err),
+        })?;
+
+    revoke_api_keys_by_account_id(&mut *tx, reset_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke API keys: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit password reset: {}", err),
+    })?;
+
+    record_auth_event(
+        pool,
+        origin,
+        Some(reset_token.account_id),
+        AuthEventType::PasswordReset,
+        None,
+    )
+    .await;
+
+    Ok(RevokedSessions { revoked_count })
+}
+
+/// Changes the password of a signed-in account after checking the current
+/// one. Every session except `session_id` is signed out and every API key
+/// revoked.
+pub async fn change_password(
+    pool: &PgPool,
+    config: &Config,
+    account: &Account,
+    session_id: &str,
+    current_password: &str,
+    new_password: &str,
+    origin: &ClientOrigin,
+) -> Result<RevokedSessions, AppError> {
+    if let Some(until) = account.locked_until
+        && until > Utc::now()
+    {
+        return Err(AppError::AccountLocked { until });
+    }
+
+    // A stolen session must not become an oracle for the current password, so
+    // wrong guesses count towards the same lockout as failed logins.
+    if let PasswordCheck::Invalid =
+        verify_password(config, current_password, &account.password).await?
+    {
+        let locked_until =
+            record_login_failure(pool, config, Some(account.id), &origin.client_ip).await?;
+        record_login_failure_event(pool, origin, Some(account.id), "invalid current password")
+            .await;
+        if let Some(until) = locked_until {
+            return Err(AppError::AccountLocked { until });
+        }
+        return Err(AppError::InvalidCredentials {
+            msg: String::from("Invalid current password"),
+        });
+    }
+
+    check_password_not_reused(pool, config, account, new_password).await?;
+
+    let password = hash_password(config, new_password).await?;
+
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    archive_account_password(&mut *tx, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to archive password: {}", err),
+        })?;
+
+    trim_password_history(&mut *tx, account.id, history_depth(config))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to trim password history: {}", err),
+        })?;
+
+    update_account_password(&mut *tx, account.id, &password)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to update password: {}", err),
+        })?;
+
+    revoke_other_refresh_tokens_by_account_id(&mut *tx, account.id, session_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke refresh tokens: {}", err),
+        })?;
+
+    let revoked_count = delete_other_sessions_by_account_id(&mut *tx, account.id, session_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete sessions: {}", err),
+        })?;
+
+    revoke_api_keys_by_account_id(&mut *tx, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke API keys: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit password change: {}", err),
+    })?;
+
+    tracing::info!(
+        "Password changed for account {}, {} other session(s) revoked",
+        account.id,
+        revoked_count
+    );
+    record_auth_event(
+        pool,
+        origin,
+        Some(account.id),
+        AuthEventType::PasswordChange,
+        None,
+    )
+    .await;
+
+    Ok(RevokedSessions { revoked_count })
+}
+
+/// Rejects a new password matching the current one or any of the most recent
+/// entries in the account's password history.
+async fn check_password_not_reused(
+    pool: &PgPool,
+    config: &Config,
+    account: &Account,
+    new_password: &str,
+) -> Result<(), AppError> {
+    if config.password_history_size <= 0 {
+        return Ok(());
+    }
+
+    let history = fetch_recent_password_history(pool, account.id, history_depth(config))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query password history: {}", err),
+        })?;
+
+    let previous = std::iter::once(account.password.as_str())
+        .chain(history.iter().map(|entry| entry.password.as_str()));
+
+    for stored_hash in previous {
+        if let PasswordCheck::Valid { .. } =
+            verify_password(config, new_password, stored_hash).await?
+        {
+            return Err(AppError::PasswordReused {
+                msg: format!(
+                    "Account {} reused one of its last {} passwords",
+                    account.id, config.password_history_size
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// History rows worth keeping: the current password is checked from the
+/// account itself, so only the `password_history_size - 1` before it matter.
+fn history_depth(config: &Config) -> i64 {
+    (config.password_history_size - 1).max(0)
+}
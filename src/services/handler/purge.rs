@@ -0,0 +1,181 @@
+use chrono::{DateTime, Duration, Utc};
+use futures::future::BoxFuture;
+use sqlx::{PgConnection, PgPool};
+
+use crate::{
+    config::Config,
+    dal::{
+        advisory_lock::{advisory_unlock, try_advisory_lock},
+        auth_event::delete_expired_auth_events,
+        data_export::delete_expired_data_exports,
+        email_verification_token::delete_expired_email_verification_tokens,
+        login_challenge::delete_expired_login_challenges,
+        login_failure_ip::delete_stale_login_failure_ips,
+        login_link_token::delete_expired_login_link_tokens,
+        oidc_login_state::delete_expired_oidc_login_states,
+        password_reset_token::delete_expired_password_reset_tokens,
+        refresh_token::delete_expired_refresh_tokens,
+        session::delete_expired_sessions,
+    },
+    services::{handler::export::data_export_path, utils::error::AppError},
+};
+
+/// Advisory lock held while purging, so only one replica purges at a time.
+/// The deletes run on the connection holding it, so purging never waits for
+/// a second connection from the pool.
+const PURGE_LOCK_KEY: i64 = 0x7075_7267_6500;
+
+/// Deletes everything whose lifetime is over: sessions, one-time tokens, OIDC
+/// login states, stale per-IP login failure counters, audit events past their
+/// retention and expired data export archives. Returns the number of deleted rows, or `None` when another
+/// replica holds the purge lock.
+pub async fn purge_expired_rows(pool: &PgPool, config: &Config) -> Result<Option<u64>, AppError> {
+    let mut lock_conn = pool.acquire().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to acquire connection: {}", err),
+    })?;
+
+    let acquired = try_advisory_lock(&mut lock_conn, PURGE_LOCK_KEY)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to take purge lock: {}", err),
+        })?;
+    if !acquired {
+        return Ok(None);
+    }
+
+    let result = purge_all(&mut lock_conn, config, Utc::now()).await;
+
+    if let Err(err) = advisory_unlock(&mut lock_conn, PURGE_LOCK_KEY).await {
+        // Closing the connection is the only other way to give the lock back.
+        tracing::error!("Failed to release purge lock: {}", err);
+        lock_conn.close_on_drop();
+    }
+
+    result.map(Some)
+}
+
+async fn purge_all(
+    conn: &mut PgConnection,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> Result<u64, AppError> {
+    let limit = config.expired_purge_batch_size;
+    let mut total = 0;
+
+    total += purge_in_batches(&mut *conn, "session", limit, |conn| {
+        Box::pin(delete_expired_sessions(conn, now, limit))
+    })
+    .await?;
+    total += purge_in_batches(&mut *conn, "refresh_token", limit, |conn| {
+        Box::pin(delete_expired_refresh_tokens(conn, now, limit))
+    })
+    .await?;
+    total += purge_in_batches(&mut *conn, "password_reset_token", limit, |conn| {
+        Box::pin(delete_expired_password_reset_tokens(conn, now, limit))
+    })
+    .await?;
+    total += purge_in_batches(&mut *conn, "email_verification_token", limit, |conn| {
+        Box::pin(delete_expired_email_verification_tokens(conn, now, limit))
+    })
+    .await?;
+    total += purge_in_batches(&mut *conn, "login_challenge", limit, |conn| {
+        Box::pin(delete_expired_login_challenges(conn, now, limit))
+    })
+    .await?;
+    total += purge_in_batches(&mut *conn, "login_link_token", limit, |conn| {
+        Box::pin(delete_expired_login_link_tokens(conn, now, limit))
+    })
+    .await?;
+    total += purge_in_batches(&mut *conn, "oidc_login_state", limit, |conn| {
+        Box::pin(delete_expired_oidc_login_states(conn, now, limit))
+    })
+    .await?;
+
+    let window_cutoff = now - Duration::seconds(config.login_ip_window_seconds);
+    total += purge_in_batches(&mut *conn, "login_failure_ip", limit, |conn| {
+        Box::pin(delete_stale_login_failure_ips(conn, window_cutoff, limit))
+    })
+    .await?;
+
+    let retention_cutoff = now - Duration::seconds(config.auth_event_retention_seconds);
+    total += purge_in_batches(&mut *conn, "auth_event", limit, |conn| {
+        Box::pin(delete_expired_auth_events(conn, retention_cutoff, limit))
+    })
+    .await?;
+
+    total += purge_expired_data_exports(conn, config, now).await?;
+
+    Ok(total)
+}
+
+/// Runs `delete` until it deletes less than a full batch.
+async fn purge_in_batches<F>(
+    conn: &mut PgConnection,
+    table: &str,
+    limit: i64,
+    delete: F,
+) -> Result<u64, AppError>
+where
+    F: for<'c> Fn(&'c mut PgConnection) -> BoxFuture<'c, Result<u64, sqlx::Error>>,
+{
+    let mut deleted = 0;
+    loop {
+        let batch = delete(&mut *conn)
+            .await
+            .map_err(|err| AppError::SqlxError {
+                msg: format!("Failed to purge expired {} rows: {}", table, err),
+            })?;
+        deleted += batch;
+        if (batch as i64) < limit {
+            break;
+        }
+    }
+
+    if deleted > 0 {
+        tracing::info!("Purged {} expired {} rows", deleted, table);
+    }
+    Ok(deleted)
+}
+
+/// Failed exports, and pending ones whose build was lost, never get a
+/// download window; they go once they are as old as a finished export would
+/// be when it expires. The rows go first: an archive left behind by a crash is
+/// only disk space, while a row without its archive would still be offered
+/// for download.
+async fn purge_expired_data_exports(
+    conn: &mut PgConnection,
+    config: &Config,
+    now: DateTime<Utc>,
+) -> Result<u64, AppError> {
+    let limit = config.expired_purge_batch_size;
+    let unfinished_cutoff = now
+        - Duration::seconds(config.data_export_build_timeout_seconds)
+        - Duration::seconds(config.data_export_ttl_seconds);
+    let mut deleted = 0;
+    loop {
+        let export_ids = delete_expired_data_exports(&mut *conn, now, unfinished_cutoff, limit)
+            .await
+            .map_err(|err| AppError::SqlxError {
+                msg: format!("Failed to purge expired data_export rows: {}", err),
+            })?;
+
+        for export_id in &export_ids {
+            let path = data_export_path(config, export_id);
+            if let Err(err) = tokio::fs::remove_file(&path).await
+                && err.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::error!("Failed to remove data export {}: {}", path.display(), err);
+            }
+        }
+
+        deleted += export_ids.len() as u64;
+        if (export_ids.len() as i64) < limit {
+            break;
+        }
+    }
+
+    if deleted > 0 {
+        tracing::info!("Purged {} expired data_export rows", deleted);
+    }
+    Ok(deleted)
+}
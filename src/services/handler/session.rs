@@ -0,0 +1,353 @@
+use chrono::{Duration, Utc};
+use sqlx::{PgConnection, PgPool};
+
+use crate::{
+    config::Config,
+    dal::{
+        account::{Account, fetch_account_by_id},
+        api_key::revoke_api_keys_by_account_id,
+        refresh_token::{
+            fetch_refresh_token_for_update, insert_refresh_token, mark_refresh_token_rotated,
+            revoke_refresh_token_family, revoke_refresh_tokens_by_account_id,
+            revoke_refresh_tokens_by_session_id,
+        },
+        session::{
+            Session, delete_session_by_id, delete_sessions_by_account_id,
+            fetch_active_sessions_by_account_id, fetch_session_by_id, insert_session,
+        },
+    },
+    services::{
+        handler::auth_event::record_auth_event,
+        model::{
+            account::LoggedAccount,
+            auth_event::{AuthEventType, ClientOrigin},
+            session::{ActiveSession, RevokedSessions},
+        },
+        utils::{
+            error::AppError,
+            token::{generate_token, hash_token},
+        },
+    },
+};
+
+/// Creates a short-lived access session plus a refresh token that starts a
+/// new rotation family.
+pub async fn issue_session(
+    pool: &PgPool,
+    config: &Config,
+    account: &Account,
+) -> Result<LoggedAccount, AppError> {
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let logged_account =
+        issue_session_in_family(&mut tx, config, account, &generate_token()).await?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit session: {}", err),
+    })?;
+
+    Ok(logged_account)
+}
+
+/// Inserts the session and its refresh token on `conn`; the caller commits.
+async fn issue_session_in_family(
+    conn: &mut PgConnection,
+    config: &Config,
+    account: &Account,
+    family_id: &str,
+) -> Result<LoggedAccount, AppError> {
+    let now = Utc::now();
+    let session_id = generate_token();
+    let session_expiry_time = now + Duration::seconds(config.session_ttl_seconds);
+    let refresh_token = generate_token();
+    let refresh_expiry_time = now + Duration::seconds(config.refresh_token_ttl_seconds);
+
+    insert_session(&mut *conn, &session_id, account.id, session_expiry_time)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to insert session: {}", err),
+        })?;
+
+    insert_refresh_token(
+        &mut *conn,
+        &hash_token(&refresh_token),
+        family_id,
+        account.id,
+        &session_id,
+        refresh_expiry_time,
+    )
+    .await
+    .map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to insert refresh token: {}", err),
+    })?;
+
+    Ok(LoggedAccount {
+        email: account.email.clone(),
+        session_id,
+        session_expire_time: session_expiry_time.to_rfc3339(),
+        refresh_token,
+        refresh_token_expire_time: refresh_expiry_time.to_rfc3339(),
+    })
+}
+
+/// Exchanges a refresh token for a new session and a rotated refresh token.
+/// Presenting a token that was already rotated means it was copied, so the
+/// whole family and every session it issued is revoked. Rotation and the new
+/// session commit together, so a failure leaves the old token usable.
+pub async fn refresh_session(
+    pool: &PgPool,
+    config: &Config,
+    origin: &ClientOrigin,
+    refresh_token: &str,
+) -> Result<LoggedAccount, AppError> {
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let token = fetch_refresh_token_for_update(&mut tx, &hash_token(refresh_token))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query refresh token: {}", err),
+        })?;
+
+    let Some(token) = token else {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Unknown refresh token"),
+        });
+    };
+
+    if token.revoked_time.is_some() {
+        return Err(AppError::InvalidToken {
+            msg: format!("Refresh token family {} is revoked", token.family_id),
+        });
+    }
+
+    if token.rotated_time.is_some() {
+        let revoked_sessions = revoke_refresh_token_family(&mut tx, &token.family_id)
+            .await
+            .map_err(|err| AppError::SqlxError {
+                msg: format!("Failed to revoke refresh token family: {}", err),
+            })?;
+        tx.commit().await.map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to commit revocation: {}", err),
+        })?;
+
+        tracing::warn!(
+            "Refresh token reuse detected for account {}: revoked family {} and {} session(s)",
+            token.account_id,
+            token.family_id,
+            revoked_sessions
+        );
+        record_auth_event(
+            pool,
+            origin,
+            Some(token.account_id),
+            AuthEventType::SessionRevoked,
+            Some("refresh token reuse"),
+        )
+        .await;
+        return Err(AppError::RefreshTokenReused {
+            msg: format!("Rotated refresh token of family {} reused", token.family_id),
+        });
+    }
+
+    if token.expiry_time <= Utc::now() {
+        return Err(AppError::InvalidToken {
+            msg: format!(
+                "Refresh token expired at {}",
+                token.expiry_time.to_rfc3339()
+            ),
+        });
+    }
+
+    let account = fetch_account_by_id(pool, token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query account: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        return Err(AppError::InvalidToken {
+            msg: format!("Refresh token owner {} no longer exists", token.account_id),
+        });
+    };
+
+    if account.disabled_at.is_some() {
+        return Err(AppError::InvalidToken {
+            msg: format!("Refresh token owner {} is disabled", account.id),
+        });
+    }
+
+    mark_refresh_token_rotated(&mut *tx, &token.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to rotate refresh token: {}", err),
+        })?;
+
+    delete_session_by_id(&mut *tx, &token.session_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete previous session: {}", err),
+        })?;
+
+    let logged_account =
+        issue_session_in_family(&mut tx, config, &account, &token.family_id).await?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit rotation: {}", err),
+    })?;
+
+    Ok(logged_account)
+}
+
+pub async fn authenticate_session(
+    pool: &PgPool,
+    session_id: &str,
+) -> Result<(Account, Session), AppError> {
+    let session =
+        fetch_session_by_id(pool, session_id)
+            .await
+            .map_err(|err| AppError::SqlxError {
+                msg: format!("Failed to query session: {}", err),
+            })?;
+
+    let Some(session) = session else {
+        return Err(AppError::InvalidSession {
+            msg: String::from("Unknown session"),
+        });
+    };
+
+    if session.expiry_time <= Utc::now() {
+        return Err(AppError::InvalidSession {
+            msg: format!("Session expired at {}", session.expiry_time.to_rfc3339()),
+        });
+    }
+
+    let account = fetch_account_by_id(pool, session.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query account: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        return Err(AppError::InvalidSession {
+            msg: format!("Session owner {} no longer exists", session.account_id),
+        });
+    };
+
+    if account.disabled_at.is_some() {
+        return Err(AppError::InvalidSession {
+            msg: format!("Session owner {} is disabled", account.id),
+        });
+    }
+
+    Ok((account, session))
+}
+
+pub async fn list_active_sessions(
+    pool: &PgPool,
+    account_id: i32,
+    current_session_id: &str,
+) -> Result<Vec<ActiveSession>, AppError> {
+    let sessions = fetch_active_sessions_by_account_id(pool, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query sessions: {}", err),
+        })?;
+
+    Ok(sessions
+        .into_iter()
+        .map(|session| ActiveSession {
+            current: session.id == current_session_id,
+            utc_create: session.utc_create.to_rfc3339(),
+            expiry_time: session.expiry_time.to_rfc3339(),
+        })
+        .collect())
+}
+
+pub async fn revoke_session(
+    pool: &PgPool,
+    origin: &ClientOrigin,
+    session: &Session,
+) -> Result<RevokedSessions, AppError> {
+    let session_id = session.id.as_str();
+
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    revoke_refresh_tokens_by_session_id(&mut *tx, session_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke refresh tokens: {}", err),
+        })?;
+
+    let revoked_count = delete_session_by_id(&mut *tx, session_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete session: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit logout: {}", err),
+    })?;
+
+    record_auth_event(
+        pool,
+        origin,
+        Some(session.account_id),
+        AuthEventType::Logout,
+        None,
+    )
+    .await;
+
+    Ok(RevokedSessions { revoked_count })
+}
+
+/// Signs an account out everywhere and revokes its API keys, so a leaked key
+/// does not outlive the sign-out. `reason` is kept in the audit log.
+pub async fn revoke_all_sessions(
+    pool: &PgPool,
+    origin: &ClientOrigin,
+    account_id: i32,
+    reason: &str,
+) -> Result<RevokedSessions, AppError> {
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    revoke_refresh_tokens_by_account_id(&mut *tx, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke refresh tokens: {}", err),
+        })?;
+
+    let revoked_count = delete_sessions_by_account_id(&mut *tx, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to delete sessions: {}", err),
+        })?;
+
+    revoke_api_keys_by_account_id(&mut *tx, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to revoke API keys: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit logout: {}", err),
+    })?;
+
+    record_auth_event(
+        pool,
+        origin,
+        Some(account_id),
+        AuthEventType::SessionRevoked,
+        Some(reason),
+    )
+    .await;
+
+    Ok(RevokedSessions { revoked_count })
+}
@@ -0,0 +1,343 @@
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sqlx::PgPool;
+
+use crate::{
+    config::Config,
+    dal::{
+        account::{Account, fetch_account_by_id, reset_account_login_failures},
+        account_totp::{
+            enable_account_totp, fetch_account_totp, update_account_totp_last_used_step,
+            upsert_pending_account_totp,
+        },
+        login_challenge::{
+            fetch_login_challenge_for_update, insert_login_challenge, mark_login_challenge_used,
+        },
+        totp_recovery_code::{replace_totp_recovery_codes, use_totp_recovery_code},
+    },
+    services::{
+        handler::{
+            account::{check_client_ip_allowed, record_login_failure, record_login_failure_event},
+            auth_event::record_auth_event,
+            session::issue_session,
+        },
+        model::{
+            account::LoggedAccount,
+            auth_event::{AuthEventType, ClientOrigin},
+            totp::{TotpChallenge, TotpSetup},
+        },
+        utils::{
+            crypto::{decrypt_secret, encrypt_secret},
+            error::AppError,
+            token::{generate_token, hash_token},
+            totp::{encode_secret, generate_secret, otpauth_uri, verify_code},
+        },
+    },
+};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// Starts enrollment by storing a new, not yet enabled secret.
+pub async fn setup_totp(
+    pool: &PgPool,
+    config: &Config,
+    account: &Account,
+) -> Result<TotpSetup, AppError> {
+    let key = encryption_key(config)?;
+
+    let existing =
+        fetch_account_totp(pool, account.id)
+            .await
+            .map_err(|err| AppError::SqlxError {
+                msg: format!("Failed to query TOTP: {}", err),
+            })?;
+
+    if existing.is_some_and(|totp| totp.enabled_at.is_some()) {
+        return Err(AppError::TotpState {
+            msg: format!("TOTP already enabled for account {}", account.id),
+        });
+    }
+
+    let secret = generate_secret();
+    upsert_pending_account_totp(pool, account.id, &encrypt_secret(key, &secret)?)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to store TOTP secret: {}", err),
+        })?;
+
+    Ok(TotpSetup {
+        secret: encode_secret(&secret),
+        otpauth_uri: otpauth_uri(&config.totp_issuer, &account.email, &secret),
+    })
+}
+
+/// Enables TOTP once the user proves their authenticator produces valid
+/// codes, and returns the plaintext recovery codes. Only their hashes are kept.
+pub async fn confirm_totp(
+    pool: &PgPool,
+    config: &Config,
+    account_id: i32,
+    code: &str,
+) -> Result<Vec<String>, AppError> {
+    let key = encryption_key(config)?;
+
+    let totp = fetch_account_totp(pool, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query TOTP: {}", err),
+        })?;
+
+    let Some(totp) = totp else {
+        return Err(AppError::TotpState {
+            msg: format!("TOTP setup not started for account {}", account_id),
+        });
+    };
+
+    if totp.enabled_at.is_some() {
+        return Err(AppError::TotpState {
+            msg: format!("TOTP already enabled for account {}", account_id),
+        });
+    }
+
+    let secret = decrypt_secret(key, &totp.secret_encrypted)?;
+    let Some(step) = verify_code(&secret, code, Utc::now(), None) else {
+        return Err(AppError::InvalidTotpCode {
+            msg: format!("Invalid TOTP confirmation code for account {}", account_id),
+        });
+    };
+
+    let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+    let code_hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|code| hash_token(&normalize_recovery_code(code)))
+        .collect();
+
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    enable_account_totp(&mut *tx, account_id, step)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to enable TOTP: {}", err),
+        })?;
+
+    replace_totp_recovery_codes(&mut tx, account_id, &code_hashes)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to store recovery codes: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit TOTP enrollment: {}", err),
+    })?;
+
+    Ok(recovery_codes)
+}
+
+pub async fn is_totp_enabled(pool: &PgPool, account_id: i32) -> Result<bool, AppError> {
+    let totp = fetch_account_totp(pool, account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query TOTP: {}", err),
+        })?;
+
+    Ok(totp.is_some_and(|totp| totp.enabled_at.is_some()))
+}
+
+pub async fn create_totp_challenge(
+    pool: &PgPool,
+    config: &Config,
+    account_id: i32,
+) -> Result<TotpChallenge, AppError> {
+    let challenge_token = generate_token();
+    let expiry_time = Utc::now() + Duration::seconds(config.totp_challenge_ttl_seconds);
+
+    insert_login_challenge(pool, &hash_token(&challenge_token), account_id, expiry_time)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to insert login challenge: {}", err),
+        })?;
+
+    Ok(TotpChallenge {
+        challenge_token,
+        challenge_expire_time: expiry_time.to_rfc3339(),
+    })
+}
+
+/// Second login step: trades a challenge plus a TOTP or recovery code for a
+/// session. Wrong codes count towards the same lockout as wrong passwords.
+pub async fn complete_totp_login(
+    pool: &PgPool,
+    config: &Config,
+    challenge_token: &str,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+    origin: &ClientOrigin,
+) -> Result<LoggedAccount, AppError> {
+    check_client_ip_allowed(pool, config, &origin.client_ip).await?;
+
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let challenge = fetch_login_challenge_for_update(&mut tx, &hash_token(challenge_token))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query login challenge: {}", err),
+        })?;
+
+    let Some(challenge) = challenge else {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Unknown login challenge"),
+        });
+    };
+
+    if challenge.used_time.is_some() || challenge.expiry_time <= Utc::now() {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Login challenge already used or expired"),
+        });
+    }
+
+    let account = fetch_account_by_id(pool, challenge.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query account: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        return Err(AppError::InvalidToken {
+            msg: format!("Challenge owner {} no longer exists", challenge.account_id),
+        });
+    };
+
+    if account.disabled_at.is_some() {
+        record_login_failure_event(pool, origin, Some(account.id), "account disabled").await;
+        return Err(AppError::AccountDisabled {
+            msg: format!("Account {} is disabled", account.id),
+        });
+    }
+
+    if let Some(until) = account.locked_until
+        && until > Utc::now()
+    {
+        record_login_failure_event(pool, origin, Some(account.id), "account locked").await;
+        return Err(AppError::AccountLocked { until });
+    }
+
+    let totp = fetch_account_totp(pool, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query TOTP: {}", err),
+        })?;
+
+    let Some(totp) = totp.filter(|totp| totp.enabled_at.is_some()) else {
+        return Err(AppError::TotpState {
+            msg: format!("TOTP is not enabled for account {}", account.id),
+        });
+    };
+
+    let accepted = match (code, recovery_code) {
+        (Some(code), _) => {
+            // Recovery codes are plain hashes and keep working without the key.
+            let secret = decrypt_secret(encryption_key(config)?, &totp.secret_encrypted)?;
+            match verify_code(&secret, code, Utc::now(), totp.last_used_step) {
+                Some(step) => update_account_totp_last_used_step(&mut *tx, account.id, step)
+                    .await
+                    .map_err(|err| AppError::SqlxError {
+                        msg: format!("Failed to record TOTP step: {}", err),
+                    })?,
+                None => false,
+            }
+        }
+        (None, Some(recovery_code)) => use_totp_recovery_code(
+            &mut *tx,
+            account.id,
+            &hash_token(&normalize_recovery_code(recovery_code)),
+        )
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to use recovery code: {}", err),
+        })?,
+        (None, None) => false,
+    };
+
+    let method = if code.is_some() {
+        "totp"
+    } else {
+        "recovery code"
+    };
+
+    if !accepted {
+        drop(tx);
+        let locked_until =
+            record_login_failure(pool, config, Some(account.id), &origin.client_ip).await?;
+        record_login_failure_event(
+            pool,
+            origin,
+            Some(account.id),
+            &format!("invalid {}", method),
+        )
+        .await;
+        if let Some(until) = locked_until {
+            return Err(AppError::AccountLocked { until });
+        }
+        return Err(AppError::InvalidTotpCode {
+            msg: format!("Invalid second factor for account {}", account.id),
+        });
+    }
+
+    mark_login_challenge_used(&mut *tx, &challenge.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to mark login challenge used: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit login challenge: {}", err),
+    })?;
+
+    reset_account_login_failures(pool, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to reset login failures: {}", err),
+        })?;
+
+    let logged_account = issue_session(pool, config, &account).await?;
+    record_auth_event(
+        pool,
+        origin,
+        Some(account.id),
+        AuthEventType::LoginSuccess,
+        Some(method),
+    )
+    .await;
+    Ok(logged_account)
+}
+
+fn encryption_key(config: &Config) -> Result<&str, AppError> {
+    config
+        .totp_encryption_key
+        .as_deref()
+        .ok_or_else(|| AppError::TotpUnavailable {
+            msg: String::from("TOTP_ENCRYPTION_KEY is not configured"),
+        })
+}
+
+fn generate_recovery_code() -> String {
+    let mut rng = rand::rng();
+    let chars: String = (0..10)
+        .map(|_| RECOVERY_CODE_ALPHABET[rng.random_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+        .collect();
+    format!("{}-{}", &chars[..5], &chars[5..])
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
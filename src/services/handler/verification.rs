@@ -0,0 +1,145 @@
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    config::Config,
+    dal::{
+        account::{fetch_account_by_email, mark_account_verified},
+        email_verification_token::{
+            fetch_email_verification_token_for_update, fetch_latest_email_verification_token,
+            insert_email_verification_token, mark_email_verification_tokens_used,
+        },
+    },
+    services::{
+        mail::{MailMessage, MailSender},
+        utils::{
+            error::AppError,
+            token::{generate_token, hash_token},
+        },
+    },
+};
+
+/// Issues a verification token and mails the link to the account.
+pub async fn send_verification_mail(
+    pool: &PgPool,
+    config: &Config,
+    mailer: &dyn MailSender,
+    account_id: i32,
+    email: &str,
+) -> Result<(), AppError> {
+    let token = generate_token();
+    let expiry_time = Utc::now() + Duration::seconds(config.email_verification_ttl_seconds);
+
+    insert_email_verification_token(pool, &hash_token(&token), account_id, expiry_time)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to insert email verification token: {}", err),
+        })?;
+
+    let message = MailMessage {
+        to: email.to_string(),
+        subject: String::from("Verify your Plug and Plant email"),
+        body: format!(
+            "Welcome to Plug and Plant!\n\n\
+             Confirm this email address before {} by opening:\n{}/verify-email?token={}",
+            expiry_time.to_rfc3339(),
+            config.frontend_base_url.trim_end_matches('/'),
+            token
+        ),
+    };
+
+    mailer.send(&message).await
+}
+
+/// Sends a fresh verification link unless the account is unknown, already
+/// verified, or was mailed less than the configured interval ago. All of those
+/// succeed silently so the endpoint cannot be used to probe for accounts.
+pub async fn resend_verification_mail(
+    pool: &PgPool,
+    config: &Config,
+    mailer: &dyn MailSender,
+    email: &str,
+) -> Result<(), AppError> {
+    let account = fetch_account_by_email(pool, email)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query: {}", err),
+        })?;
+
+    let Some(account) = account else {
+        tracing::info!("Verification resend requested for unknown email");
+        return Ok(());
+    };
+
+    if account.verified_at.is_some() {
+        tracing::info!("Account {} is already verified", account.id);
+        return Ok(());
+    }
+
+    let latest = fetch_latest_email_verification_token(pool, account.id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query email verification token: {}", err),
+        })?;
+
+    let throttle = Duration::seconds(config.email_verification_resend_interval_seconds);
+    if let Some(latest) = latest
+        && latest.utc_create + throttle > Utc::now()
+    {
+        tracing::info!("Verification resend throttled for account {}", account.id);
+        return Ok(());
+    }
+
+    send_verification_mail(pool, config, mailer, account.id, &account.email).await
+}
+
+pub async fn verify_email(pool: &PgPool, token: &str) -> Result<(), AppError> {
+    let mut tx = pool.begin().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to begin transaction: {}", err),
+    })?;
+
+    let verification_token = fetch_email_verification_token_for_update(&mut tx, &hash_token(token))
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to query email verification token: {}", err),
+        })?;
+
+    let Some(verification_token) = verification_token else {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Unknown email verification token"),
+        });
+    };
+
+    if verification_token.used_time.is_some() {
+        return Err(AppError::InvalidToken {
+            msg: String::from("Email verification token already used"),
+        });
+    }
+
+    if verification_token.expiry_time <= Utc::now() {
+        return Err(AppError::InvalidToken {
+            msg: format!(
+                "Email verification token expired at {}",
+                verification_token.expiry_time.to_rfc3339()
+            ),
+        });
+    }
+
+    mark_account_verified(&mut *tx, verification_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to verify account: {}", err),
+        })?;
+
+    mark_email_verification_tokens_used(&mut *tx, verification_token.account_id)
+        .await
+        .map_err(|err| AppError::SqlxError {
+            msg: format!("Failed to mark email verification token used: {}", err),
+        })?;
+
+    tx.commit().await.map_err(|err| AppError::SqlxError {
+        msg: format!("Failed to commit email verification: {}", err),
+    })?;
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+use crate::{config::Config, services::handler::deletion::purge_deleted_accounts};
+
+/// Periodically purges accounts whose deletion grace window is over. A full
+/// batch is followed immediately by the next one until the backlog is gone or
+/// the server shuts down.
+pub fn spawn_account_deletion_job(
+    pool: PgPool,
+    config: Arc<Config>,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(
+            config.account_deletion_interval_seconds,
+        ));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            loop {
+                let span = tracing::info_span!(parent: None, "account_deletion");
+                match purge_deleted_accounts(&pool, &config)
+                    .instrument(span)
+                    .await
+                {
+                    Ok(deleted)
+                        if deleted as i64 >= config.account_deletion_batch_size
+                            && !shutdown.is_cancelled() =>
+                    {
+                        continue;
+                    }
+                    Ok(_) => break,
+                    Err(err) => {
+                        tracing::error!("Account deletion job failed: {:?}", err);
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,40 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+use crate::{config::Config, services::handler::purge::purge_expired_rows};
+
+/// Periodically deletes expired sessions, tokens and export archives. Each
+/// replica runs the job; the purge itself skips the run when another replica
+/// is already purging.
+pub fn spawn_expired_purge_job(
+    pool: PgPool,
+    config: Arc<Config>,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(config.expired_purge_interval_seconds));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            // One trace per run rather than one per query.
+            let span = tracing::info_span!(parent: None, "expired_purge");
+            match purge_expired_rows(&pool, &config).instrument(span).await {
+                Ok(Some(_)) => {}
+                Ok(None) => {
+                    tracing::debug!("Expired purge skipped: another replica holds the lock")
+                }
+                Err(err) => tracing::error!("Expired purge job failed: {:?}", err),
+            }
+        }
+    })
+}
@@ -0,0 +1,2 @@
+pub mod account_deletion;
+pub mod expired_purge;
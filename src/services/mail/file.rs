@@ -0,0 +1,68 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use futures::future::BoxFuture;
+use uuid::Uuid;
+
+use crate::services::{
+    mail::{MailMessage, MailSender},
+    utils::error::AppError,
+};
+
+/// Local development sink: writes each mail to `<dir>/<timestamp>-<uuid>.eml`,
+/// or prints it to stdout when no directory is set.
+#[derive(Debug)]
+pub struct FileMailSender {
+    dir: Option<PathBuf>,
+    from: String,
+}
+
+impl FileMailSender {
+    pub fn new(dir: Option<PathBuf>, from: &str) -> Self {
+        Self {
+            dir,
+            from: from.to_string(),
+        }
+    }
+}
+
+impl MailSender for FileMailSender {
+    fn send<'a>(&'a self, message: &'a MailMessage) -> BoxFuture<'a, Result<(), AppError>> {
+        Box::pin(async move {
+            let now = Utc::now();
+            let content = format!(
+                "From: {}\nTo: {}\nDate: {}\nSubject: {}\n\n{}\n",
+                self.from,
+                message.to,
+                now.to_rfc2822(),
+                message.subject,
+                message.body
+            );
+
+            let Some(dir) = &self.dir else {
+                println!("{}", content);
+                return Ok(());
+            };
+
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|err| AppError::MailError {
+                    msg: format!("Failed to create mail directory {}: {}", dir.display(), err),
+                })?;
+
+            let path = dir.join(format!(
+                "{}-{}.eml",
+                now.format("%Y%m%dT%H%M%S%.3f"),
+                Uuid::new_v4()
+            ));
+            tokio::fs::write(&path, content)
+                .await
+                .map_err(|err| AppError::MailError {
+                    msg: format!("Failed to write mail {}: {}", path.display(), err),
+                })?;
+
+            tracing::info!("Mail to {} written to {}", message.to, path.display());
+            Ok(())
+        })
+    }
+}
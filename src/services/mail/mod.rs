@@ -0,0 +1,51 @@
+use std::{fmt::Debug, sync::Arc};
+
+use clap::ValueEnum;
+use futures::future::BoxFuture;
+
+use crate::{
+    config::Config,
+    services::{
+        mail::{file::FileMailSender, smtp::SmtpMailSender},
+        utils::error::AppError,
+    },
+};
+
+pub mod file;
+pub mod smtp;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum MailTransport {
+    Smtp,
+    File,
+    Stdout,
+}
+
+#[derive(Debug)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers transactional mail such as password reset links.
+pub trait MailSender: Send + Sync + Debug {
+    fn send<'a>(&'a self, message: &'a MailMessage) -> BoxFuture<'a, Result<(), AppError>>;
+}
+
+pub fn build_mail_sender(config: &Config) -> anyhow::Result<Arc<dyn MailSender>> {
+    let sender: Arc<dyn MailSender> = match config.mail_transport {
+        MailTransport::Smtp => {
+            let Some(smtp_url) = &config.smtp_url else {
+                anyhow::bail!("SMTP_URL is required when MAIL_TRANSPORT is smtp");
+            };
+            Arc::new(SmtpMailSender::new(smtp_url, &config.mail_from)?)
+        }
+        MailTransport::File => Arc::new(FileMailSender::new(
+            Some(config.mail_file_dir.clone()),
+            &config.mail_from,
+        )),
+        MailTransport::Stdout => Arc::new(FileMailSender::new(None, &config.mail_from)),
+    };
+    Ok(sender)
+}
@@ -0,0 +1,265 @@
+use std::{fmt, time::Duration};
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+use tracing::{
+    Event, Level, Subscriber,
+    field::{Field, Visit},
+};
+use tracing_subscriber::{Layer, filter::Targets, layer::Context, registry::LookupSpan};
+
+use crate::services::model::auth_event::AuthEventType;
+
+/// Every metric the server exports, kept in one registry so `/metrics`
+/// renders exactly these.
+pub static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_request_duration: HistogramVec,
+    registrations: IntCounter,
+    logins: IntCounterVec,
+    login_failures: IntCounterVec,
+    db_pool_size: IntGauge,
+    db_pool_idle: IntGauge,
+    db_pool_max_size: IntGauge,
+    db_pool_acquire_duration: Histogram,
+}
+
+/// Pool figures sampled when metrics are scraped.
+pub struct PoolStats {
+    pub size: u32,
+    pub idle: usize,
+    pub max_size: u32,
+}
+
+/// Event sqlx emits after every pool acquire once the pool's
+/// `acquire_time_level` is set, and its field with the wait in seconds
+/// (spelled as sqlx spells it).
+const POOL_ACQUIRE_TARGET: &str = "sqlx::pool::acquire";
+const POOL_ACQUIRE_FIELD: &str = "aquired_after_secs";
+
+const HTTP_LABELS: [&str; 4] = ["method", "route", "status", "response_code"];
+
+const HTTP_METHODS: [&str; 9] = [
+    "GET", "HEAD", "POST", "PUT", "DELETE", "CONNECT", "OPTIONS", "TRACE", "PATCH",
+];
+
+fn method_label(method: &str) -> &str {
+    if HTTP_METHODS.contains(&method) {
+        method
+    } else {
+        "OTHER"
+    }
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Handled HTTP requests."),
+            &HTTP_LABELS,
+        )
+        .unwrap();
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time from receiving a request to its response being ready.",
+            ),
+            &HTTP_LABELS,
+        )
+        .unwrap();
+        let registrations =
+            IntCounter::new("account_registrations_total", "Registered accounts.").unwrap();
+        let logins = IntCounterVec::new(
+            Opts::new("account_logins_total", "Successful logins by method."),
+            &["method"],
+        )
+        .unwrap();
+        let login_failures = IntCounterVec::new(
+            Opts::new("account_login_failures_total", "Rejected logins by reason."),
+            &["reason"],
+        )
+        .unwrap();
+        let db_pool_size = IntGauge::new(
+            "db_pool_connections",
+            "Open database connections, idle or in use.",
+        )
+        .unwrap();
+        let db_pool_idle =
+            IntGauge::new("db_pool_idle_connections", "Idle database connections.").unwrap();
+        let db_pool_max_size = IntGauge::new(
+            "db_pool_max_connections",
+            "Database connections the pool may open.",
+        )
+        .unwrap();
+        let db_pool_acquire_duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "db_pool_acquire_duration_seconds",
+                "Time spent waiting for a database connection from the pool.",
+            )
+            .buckets(vec![
+                0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+                10.0, 30.0,
+            ]),
+        )
+        .unwrap();
+
+        registry.register(Box::new(http_requests.clone())).unwrap();
+        registry
+            .register(Box::new(http_request_duration.clone()))
+            .unwrap();
+        registry.register(Box::new(registrations.clone())).unwrap();
+        registry.register(Box::new(logins.clone())).unwrap();
+        registry.register(Box::new(login_failures.clone())).unwrap();
+        registry.register(Box::new(db_pool_size.clone())).unwrap();
+        registry.register(Box::new(db_pool_idle.clone())).unwrap();
+        registry
+            .register(Box::new(db_pool_max_size.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(db_pool_acquire_duration.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            http_requests,
+            http_request_duration,
+            registrations,
+            logins,
+            login_failures,
+            db_pool_size,
+            db_pool_idle,
+            db_pool_max_size,
+            db_pool_acquire_duration,
+        }
+    }
+
+    /// `route` is the matched route template, never the raw path, so ids in
+    /// paths do not create a series each. Methods outside the standard set
+    /// are counted as `OTHER` for the same reason.
+    pub fn observe_http_request(
+        &self,
+        method: &str,
+        route: &str,
+        status: u16,
+        response_code: &str,
+        duration: Duration,
+    ) {
+        let status = status.to_string();
+        let labels = [method_label(method), route, status.as_str(), response_code];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_request_duration
+            .with_label_values(&labels)
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Business counters, fed from the audit log. `detail` is the login
+    /// method or the failure reason, both from a fixed set.
+    pub fn observe_auth_event(&self, event_type: AuthEventType, detail: Option<&str>) {
+        match event_type {
+            AuthEventType::Register => self.registrations.inc(),
+            AuthEventType::LoginSuccess => self
+                .logins
+                .with_label_values(&[detail.unwrap_or_default()])
+                .inc(),
+            AuthEventType::LoginFailure => self
+                .login_failures
+                .with_label_values(&[detail.unwrap_or_default()])
+                .inc(),
+            _ => {}
+        }
+    }
+
+    /// Prometheus text exposition of all metrics, with the pool gauges set
+    /// from `pool` first.
+    pub fn render(&self, pool: &PoolStats) -> String {
+        self.db_pool_size.set(i64::from(pool.size));
+        self.db_pool_idle.set(pool.idle as i64);
+        self.db_pool_max_size.set(i64::from(pool.max_size));
+
+        let mut buffer = Vec::new();
+        if let Err(err) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", err);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+/// Records the wait of every pool acquire from the events sqlx emits. The
+/// layer filters for itself, so it sees them whatever `RUST_LOG` lets through
+/// to the log output.
+pub fn pool_acquire_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    PoolAcquireLayer.with_filter(Targets::new().with_target(POOL_ACQUIRE_TARGET, Level::TRACE))
+}
+
+struct PoolAcquireLayer;
+
+impl<S: Subscriber> Layer<S> for PoolAcquireLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut wait = AcquireWait(None);
+        event.record(&mut wait);
+        if let Some(seconds) = wait.0 {
+            METRICS.db_pool_acquire_duration.observe(seconds);
+        }
+    }
+}
+
+struct AcquireWait(Option<f64>);
+
+impl Visit for AcquireWait {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == POOL_ACQUIRE_FIELD {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn keeps_standard_methods() {
+        assert_eq!(method_label("GET"), "GET");
+        assert_eq!(method_label("PATCH"), "PATCH");
+    }
+
+    #[test]
+    fn folds_unknown_methods_into_other() {
+        assert_eq!(method_label("PROPFIND"), "OTHER");
+        assert_eq!(method_label("get"), "OTHER");
+    }
+
+    #[test]
+    fn records_pool_acquire_waits() {
+        let subscriber = tracing_subscriber::registry().with(pool_acquire_layer());
+        let before = METRICS.db_pool_acquire_duration.get_sample_count();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::trace!(
+                target: "sqlx::pool::acquire",
+                aquired_after_secs = 0.25,
+                "acquired connection"
+            );
+            tracing::trace!(target: "sqlx::query", aquired_after_secs = 0.25, "unrelated");
+        });
+
+        assert_eq!(
+            METRICS.db_pool_acquire_duration.get_sample_count(),
+            before + 1
+        );
+    }
+}
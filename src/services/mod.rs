@@ -1,3 +1,8 @@
 pub mod handler;
+pub mod job;
+pub mod mail;
+pub mod metrics;
 pub mod model;
+pub mod oidc;
+pub mod telemetry;
 pub mod utils;
@@ -1,3 +1,8 @@
+use crate::{
+    dal::account::{Account, AccountRole},
+    services::model::totp::TotpChallenge,
+};
+
 #[derive(serde::Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SavedAccount {
@@ -10,4 +15,42 @@ pub struct LoggedAccount {
     pub email: String,
     pub session_id: String,
     pub session_expire_time: String,
+    pub refresh_token: String,
+    pub refresh_token_expire_time: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProfile {
+    pub email: String,
+    pub role: AccountRole,
+    pub utc_create: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete_after: Option<String>,
+}
+
+impl From<&Account> for AccountProfile {
+    fn from(account: &Account) -> Self {
+        AccountProfile {
+            email: account.email.clone(),
+            role: account.role,
+            utc_create: account.utc_create.to_rfc3339(),
+            delete_after: account.delete_after.map(|time| time.to_rfc3339()),
+        }
+    }
+}
+
+/// What a successful first login step yields: a session, or a challenge
+/// when the account has a second factor enabled.
+#[derive(Debug)]
+pub enum LoginOutcome {
+    LoggedIn(LoggedAccount),
+    TotpRequired(TotpChallenge),
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledDeletion {
+    pub delete_after: String,
+    pub revoked_count: u64,
 }
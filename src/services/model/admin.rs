@@ -0,0 +1,50 @@
+use crate::dal::account::{Account, AccountRole};
+
+/// An account as support staff see it. The password hash never leaves the
+/// service layer.
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAccount {
+    pub id: i32,
+    pub email: String,
+    pub role: AccountRole,
+    pub utc_create: String,
+    pub utc_modified: String,
+    pub verified_at: Option<String>,
+    pub disabled_at: Option<String>,
+    pub locked_until: Option<String>,
+    pub delete_after: Option<String>,
+}
+
+impl From<&Account> for AdminAccount {
+    fn from(account: &Account) -> Self {
+        AdminAccount {
+            id: account.id,
+            email: account.email.clone(),
+            role: account.role,
+            utc_create: account.utc_create.to_rfc3339(),
+            utc_modified: account.utc_modified.to_rfc3339(),
+            verified_at: account.verified_at.map(|time| time.to_rfc3339()),
+            disabled_at: account.disabled_at.map(|time| time.to_rfc3339()),
+            locked_until: account.locked_until.map(|time| time.to_rfc3339()),
+            delete_after: account.delete_after.map(|time| time.to_rfc3339()),
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAccountPage {
+    pub accounts: Vec<AdminAccount>,
+    pub page: i64,
+    pub page_size: i64,
+    pub total_count: i64,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAccountDetail {
+    pub account: AdminAccount,
+    pub totp_enabled: bool,
+    pub active_session_count: usize,
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+
+use crate::dal::api_key::ApiKey;
+
+/// What an API key may do. Session callers hold every scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ApiKeyScope {
+    AccountRead,
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub fn parse(value: &str) -> Option<ApiKeyScope> {
+        match value {
+            "account:read" => Some(ApiKeyScope::AccountRead),
+            "admin" => Some(ApiKeyScope::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::AccountRead => "account:read",
+            ApiKeyScope::Admin => "admin",
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeySummary {
+    pub id: i32,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub expiry_time: Option<String>,
+    pub last_used_time: Option<String>,
+    pub revoked_time: Option<String>,
+    pub utc_create: String,
+}
+
+impl From<&ApiKey> for ApiKeySummary {
+    fn from(api_key: &ApiKey) -> Self {
+        ApiKeySummary {
+            id: api_key.id,
+            name: api_key.name.clone(),
+            key_prefix: api_key.key_prefix.clone(),
+            scopes: api_key.scopes.clone(),
+            expiry_time: api_key.expiry_time.map(|time| time.to_rfc3339()),
+            last_used_time: api_key.last_used_time.map(|time| time.to_rfc3339()),
+            revoked_time: api_key.revoked_time.map(|time| time.to_rfc3339()),
+            utc_create: api_key.utc_create.to_rfc3339(),
+        }
+    }
+}
+
+/// A freshly minted key. `key` is returned once and never stored.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKey {
+    pub key: String,
+    #[serde(flatten)]
+    pub summary: ApiKeySummary,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_round_trip_through_their_names() {
+        for scope in [ApiKeyScope::AccountRead, ApiKeyScope::Admin] {
+            assert_eq!(ApiKeyScope::parse(scope.as_str()), Some(scope));
+        }
+        assert_eq!(ApiKeyScope::parse("account:write"), None);
+        assert_eq!(ApiKeyScope::parse("ADMIN"), None);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::dal::auth_event::AuthEvent;
+
+/// Where a request came from, as recorded in the audit log.
+#[derive(Clone, Debug)]
+pub struct ClientOrigin {
+    pub client_ip: String,
+    pub user_agent: Option<String>,
+    pub request_id: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthEventType {
+    Register,
+    LoginSuccess,
+    LoginFailure,
+    Logout,
+    SessionRevoked,
+    PasswordChange,
+    PasswordReset,
+}
+
+impl AuthEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthEventType::Register => "register",
+            AuthEventType::LoginSuccess => "login_success",
+            AuthEventType::LoginFailure => "login_failure",
+            AuthEventType::Logout => "logout",
+            AuthEventType::SessionRevoked => "session_revoked",
+            AuthEventType::PasswordChange => "password_change",
+            AuthEventType::PasswordReset => "password_reset",
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthEventEntry {
+    pub event_type: String,
+    pub detail: Option<String>,
+    pub client_ip: String,
+    pub user_agent: Option<String>,
+    pub request_id: String,
+    pub utc_create: String,
+}
+
+impl From<AuthEvent> for AuthEventEntry {
+    fn from(event: AuthEvent) -> Self {
+        AuthEventEntry {
+            event_type: event.event_type,
+            detail: event.detail,
+            client_ip: event.client_ip,
+            user_agent: event.user_agent,
+            request_id: event.request_id,
+            utc_create: event.utc_create.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthEventPage {
+    pub events: Vec<AuthEventEntry>,
+    pub page: i64,
+    pub page_size: i64,
+    pub total_count: i64,
+}
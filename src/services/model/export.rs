@@ -0,0 +1,97 @@
+use crate::{dal::data_export::DataExport, services::model::auth_event::AuthEventEntry};
+
+/// Status of a data export job. `download_url` is only known when the job is
+/// created, since the database keeps a hash of its token.
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataExportJob {
+    pub status: String,
+    pub utc_create: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ready_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+}
+
+impl From<&DataExport> for DataExportJob {
+    fn from(export: &DataExport) -> Self {
+        DataExportJob {
+            status: export.status.clone(),
+            utc_create: export.utc_create.to_rfc3339(),
+            ready_time: export.ready_time.map(|time| time.to_rfc3339()),
+            expiry_time: export.expiry_time.map(|time| time.to_rfc3339()),
+            download_url: None,
+        }
+    }
+}
+
+/// Everything stored about an account. Secrets and their hashes are left out;
+/// their existence and lifecycle timestamps are kept.
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DataExportArchive {
+    pub generated_at: String,
+    pub account: ExportedAccount,
+    pub sessions: Vec<ExportedToken>,
+    pub refresh_tokens: Vec<ExportedRefreshToken>,
+    pub password_reset_tokens: Vec<ExportedToken>,
+    pub email_verification_tokens: Vec<ExportedToken>,
+    pub login_challenges: Vec<ExportedToken>,
+    pub login_link_tokens: Vec<ExportedToken>,
+    pub totp: Option<ExportedTotp>,
+    pub totp_recovery_codes: Vec<ExportedToken>,
+    pub password_changes: Vec<String>,
+    pub linked_identities: Vec<ExportedIdentity>,
+    pub auth_events: Vec<AuthEventEntry>,
+    pub data_exports: Vec<DataExportJob>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedAccount {
+    pub id: i32,
+    pub email: String,
+    pub utc_create: String,
+    pub utc_modified: String,
+    pub verified_at: Option<String>,
+    pub failed_login_count: i32,
+    pub lockout_count: i32,
+    pub locked_until: Option<String>,
+    pub delete_after: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedToken {
+    pub utc_create: String,
+    pub expiry_time: Option<String>,
+    pub used_time: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedRefreshToken {
+    pub utc_create: String,
+    pub expiry_time: String,
+    pub rotated_time: Option<String>,
+    pub revoked_time: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedTotp {
+    pub utc_create: String,
+    pub enabled_at: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedIdentity {
+    pub provider: String,
+    pub subject: String,
+    pub email: String,
+    pub utc_create: String,
+    pub last_login_time: Option<String>,
+}
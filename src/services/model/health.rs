@@ -0,0 +1,44 @@
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessReport {
+    pub status: HealthStatus,
+    pub shutting_down: bool,
+    pub database: DatabaseCheck,
+    pub migrations: MigrationCheck,
+    pub pool: PoolCheck,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseCheck {
+    pub status: HealthStatus,
+    pub latency_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationCheck {
+    pub status: HealthStatus,
+    /// Versions this binary ships that the database has not applied.
+    pub pending: Vec<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolCheck {
+    pub status: HealthStatus,
+    pub size: u32,
+    pub idle: usize,
+    pub max_size: u32,
+}
@@ -1 +1,9 @@
 pub mod account;
+pub mod admin;
+pub mod api_key;
+pub mod auth_event;
+pub mod export;
+pub mod health;
+pub mod oidc;
+pub mod session;
+pub mod totp;
@@ -0,0 +1,18 @@
+/// What the browser comes back to the callback with.
+pub struct OidcCallback<'a> {
+    pub code: &'a str,
+    pub state: &'a str,
+    /// The state cookie set when the login started, if the browser kept it.
+    pub browser_state: Option<&'a str>,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcAuthorization {
+    pub authorization_url: String,
+    /// Handed to the browser as a cookie rather than in the body; the
+    /// callback only succeeds where it comes back.
+    #[serde(skip)]
+    pub state: String,
+    pub state_expire_time: String,
+}
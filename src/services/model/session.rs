@@ -0,0 +1,13 @@
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSession {
+    pub current: bool,
+    pub utc_create: String,
+    pub expiry_time: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokedSessions {
+    pub revoked_count: u64,
+}
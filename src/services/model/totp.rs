@@ -0,0 +1,13 @@
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpSetup {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpChallenge {
+    pub challenge_token: String,
+    pub challenge_expire_time: String,
+}
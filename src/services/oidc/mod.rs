@@ -0,0 +1,386 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{
+    Algorithm, DecodingKey, Validation, decode, decode_header,
+    jwk::{Jwk, JwkSet},
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use sha2::{Digest, Sha256};
+use tokio::sync::RwLock;
+
+use crate::{config::Config, services::utils::error::AppError};
+
+/// An OpenID Connect provider accounts can sign in with. Providers are
+/// configured as a JSON array in `OIDC_PROVIDERS`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OidcProviderConfig {
+    /// Short name used in the login URLs, e.g. `google`.
+    pub name: String,
+    /// Issuer identifier; discovery is read from `{issuer}/.well-known/openid-configuration`.
+    pub issuer: String,
+    pub client_id: String,
+    /// Sent with `client_secret_post`. Public clients rely on PKCE alone.
+    pub client_secret: Option<String>,
+    #[serde(default = "default_scopes")]
+    pub scopes: String,
+    /// Where the provider sends the browser back to. Defaults to the callback
+    /// endpoint of this server.
+    pub redirect_uri: Option<String>,
+}
+
+fn default_scopes() -> String {
+    String::from("openid email profile")
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OidcProviders(pub Vec<OidcProviderConfig>);
+
+impl OidcProviders {
+    pub fn find(&self, name: &str) -> Option<&OidcProviderConfig> {
+        self.0.iter().find(|provider| provider.name == name)
+    }
+}
+
+/// Parses `OIDC_PROVIDERS`; used as a clap value parser.
+pub fn parse_oidc_providers(value: &str) -> Result<OidcProviders, String> {
+    let providers: Vec<OidcProviderConfig> =
+        serde_json::from_str(value).map_err(|err| format!("invalid provider list: {}", err))?;
+
+    for (index, provider) in providers.iter().enumerate() {
+        if provider.name.is_empty()
+            || !provider
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("invalid provider name: {:?}", provider.name));
+        }
+        if providers[..index]
+            .iter()
+            .any(|other| other.name == provider.name)
+        {
+            return Err(format!("duplicate provider name: {}", provider.name));
+        }
+    }
+
+    Ok(OidcProviders(providers))
+}
+
+/// PKCE `S256` challenge for a code verifier.
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// Claims of a verified ID token this server relies on.
+#[derive(Debug, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_email_verified")]
+    pub email_verified: bool,
+    pub nonce: Option<String>,
+}
+
+/// Some providers send `email_verified` as the string `"true"`.
+fn deserialize_email_verified<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Text(String),
+    }
+
+    Ok(match Option::<Flag>::deserialize(deserializer)? {
+        Some(Flag::Bool(value)) => value,
+        Some(Flag::Text(value)) => value == "true",
+        None => false,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug)]
+struct CachedProvider {
+    metadata: Arc<ProviderMetadata>,
+    jwks: Arc<JwkSet>,
+    fetched_at: Instant,
+}
+
+/// Talks to OIDC providers. Discovery documents and signing keys are cached
+/// per issuer and refetched once they are older than the configured TTL, or
+/// when a token is signed with a key that is not in the cached set.
+#[derive(Debug)]
+pub struct OidcClient {
+    http: reqwest::Client,
+    cache_ttl: Duration,
+    cache: RwLock<HashMap<String, CachedProvider>>,
+}
+
+impl OidcClient {
+    pub fn new(config: &Config) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.oidc_http_timeout_seconds))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            http,
+            cache_ttl: Duration::from_secs(config.oidc_metadata_cache_seconds),
+            cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub async fn metadata(
+        &self,
+        provider: &OidcProviderConfig,
+    ) -> Result<Arc<ProviderMetadata>, AppError> {
+        if let Some(cached) = self.cache.read().await.get(&provider.issuer)
+            && cached.fetched_at.elapsed() < self.cache_ttl
+        {
+            return Ok(cached.metadata.clone());
+        }
+
+        Ok(self.refresh(provider).await?.0)
+    }
+
+    /// Redeems an authorization code and returns the raw ID token.
+    pub async fn exchange_code(
+        &self,
+        provider: &OidcProviderConfig,
+        redirect_uri: &str,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<String, AppError> {
+        let metadata = self.metadata(provider).await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+            ("client_id", provider.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = &provider.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form)
+            .finish();
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                "application/x-www-form-urlencoded",
+            )
+            .header(reqwest::header::ACCEPT, "application/json")
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| AppError::OidcProvider {
+                msg: format!("Token request to {} failed: {}", provider.name, err),
+            })?;
+
+        let status = response.status();
+        if status.is_client_error() {
+            let detail = response.text().await.unwrap_or_default();
+            return Err(AppError::InvalidOidcLogin {
+                msg: format!(
+                    "{} rejected the authorization code ({}): {}",
+                    provider.name, status, detail
+                ),
+            });
+        }
+        if !status.is_success() {
+            return Err(AppError::OidcProvider {
+                msg: format!("Token endpoint of {} answered {}", provider.name, status),
+            });
+        }
+
+        let token: TokenResponse = response
+            .json()
+            .await
+            .map_err(|err| AppError::OidcProvider {
+                msg: format!("Invalid token response from {}: {}", provider.name, err),
+            })?;
+
+        token.id_token.ok_or_else(|| AppError::OidcProvider {
+            msg: format!("Token response from {} has no id_token", provider.name),
+        })
+    }
+
+    /// Checks signature, issuer, audience, expiry and nonce of an ID token.
+    pub async fn verify_id_token(
+        &self,
+        provider: &OidcProviderConfig,
+        id_token: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, AppError> {
+        let invalid = |msg: String| AppError::InvalidOidcLogin { msg };
+
+        let header = decode_header(id_token)
+            .map_err(|err| invalid(format!("Malformed ID token: {}", err)))?;
+
+        // Only asymmetric signatures: a shared-secret algorithm would let
+        // anyone holding the public key forge tokens.
+        if matches!(
+            header.alg,
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+        ) {
+            return Err(invalid(format!(
+                "ID token uses unsupported algorithm {:?}",
+                header.alg
+            )));
+        }
+
+        let jwk = match self
+            .signing_key(provider, header.kid.as_deref(), false)
+            .await?
+        {
+            Some(jwk) => jwk,
+            None => self
+                .signing_key(provider, header.kid.as_deref(), true)
+                .await?
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "No signing key {:?} published by {}",
+                        header.kid, provider.name
+                    ))
+                })?,
+        };
+
+        if let Some(key_algorithm) = jwk.common.key_algorithm
+            && key_algorithm.to_string().parse::<Algorithm>().ok() != Some(header.alg)
+        {
+            return Err(invalid(format!(
+                "ID token algorithm {:?} does not match key algorithm {}",
+                header.alg, key_algorithm
+            )));
+        }
+
+        let key = DecodingKey::from_jwk(&jwk)
+            .map_err(|err| invalid(format!("Unusable signing key: {}", err)))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&provider.issuer]);
+        validation.set_audience(&[&provider.client_id]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|err| invalid(format!("ID token rejected: {}", err)))?
+            .claims;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(invalid(String::from("ID token nonce does not match")));
+        }
+
+        Ok(claims)
+    }
+
+    async fn signing_key(
+        &self,
+        provider: &OidcProviderConfig,
+        kid: Option<&str>,
+        refresh: bool,
+    ) -> Result<Option<Jwk>, AppError> {
+        let jwks = if refresh {
+            self.refresh(provider).await?.1
+        } else {
+            let cached = self
+                .cache
+                .read()
+                .await
+                .get(&provider.issuer)
+                .filter(|cached| cached.fetched_at.elapsed() < self.cache_ttl)
+                .map(|cached| cached.jwks.clone());
+            match cached {
+                Some(jwks) => jwks,
+                None => self.refresh(provider).await?.1,
+            }
+        };
+
+        let key = match kid {
+            Some(kid) => jwks.find(kid).cloned(),
+            // Without a key id the provider must publish exactly one key.
+            None if jwks.keys.len() == 1 => jwks.keys.first().cloned(),
+            None => None,
+        };
+        Ok(key)
+    }
+
+    async fn refresh(
+        &self,
+        provider: &OidcProviderConfig,
+    ) -> Result<(Arc<ProviderMetadata>, Arc<JwkSet>), AppError> {
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            provider.issuer.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = self.fetch_json(provider, &discovery_url).await?;
+
+        if metadata.issuer != provider.issuer {
+            return Err(AppError::OidcProvider {
+                msg: format!(
+                    "Discovery of {} announces issuer {} instead of {}",
+                    provider.name, metadata.issuer, provider.issuer
+                ),
+            });
+        }
+
+        let jwks: JwkSet = self.fetch_json(provider, &metadata.jwks_uri).await?;
+
+        let metadata = Arc::new(metadata);
+        let jwks = Arc::new(jwks);
+        self.cache.write().await.insert(
+            provider.issuer.clone(),
+            CachedProvider {
+                metadata: metadata.clone(),
+                jwks: jwks.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        tracing::info!("Loaded OIDC metadata for provider {}", provider.name);
+
+        Ok((metadata, jwks))
+    }
+
+    async fn fetch_json<T: DeserializeOwned>(
+        &self,
+        provider: &OidcProviderConfig,
+        url: &str,
+    ) -> Result<T, AppError> {
+        let to_error = |err: reqwest::Error| AppError::OidcProvider {
+            msg: format!("Failed to fetch {} for {}: {}", url, provider.name, err),
+        };
+
+        self.http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(to_error)?
+            .json()
+            .await
+            .map_err(to_error)
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use anyhow::Context;
+use chrono::{DateTime, SecondsFormat, Utc};
+use opentelemetry::{KeyValue, trace::Status};
+use opentelemetry_sdk::{
+    error::{OTelSdkError, OTelSdkResult},
+    trace::{SpanData, SpanExporter},
+};
+use serde_json::{Map, Value, json};
+
+/// Local development sink: appends each finished span to a file as one JSON
+/// object per line, so traces can be inspected without a collector.
+#[derive(Debug)]
+pub struct FileSpanExporter {
+    file: Mutex<File>,
+}
+
+impl FileSpanExporter {
+    pub fn new(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("could not open trace file {}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl SpanExporter for FileSpanExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let mut lines = String::new();
+        for span in &batch {
+            lines.push_str(&span_to_json(span).to_string());
+            lines.push('\n');
+        }
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|err| OTelSdkError::InternalFailure(err.to_string()))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|()| file.flush())
+            .map_err(|err| OTelSdkError::InternalFailure(format!("Failed to write spans: {}", err)))
+    }
+}
+
+fn span_to_json(span: &SpanData) -> Value {
+    let (status, status_message) = match &span.status {
+        Status::Unset => ("unset", None),
+        Status::Ok => ("ok", None),
+        Status::Error { description } => ("error", Some(description.to_string())),
+    };
+    let events: Vec<Value> = span
+        .events
+        .events
+        .iter()
+        .map(|event| {
+            json!({
+                "name": event.name,
+                "time": format_time(event.timestamp),
+                "attributes": attributes_to_json(&event.attributes),
+            })
+        })
+        .collect();
+
+    json!({
+        "traceId": span.span_context.trace_id().to_string(),
+        "spanId": span.span_context.span_id().to_string(),
+        "parentSpanId": span.parent_span_id.to_string(),
+        "name": span.name,
+        "kind": format!("{:?}", span.span_kind),
+        "startTime": format_time(span.start_time),
+        "endTime": format_time(span.end_time),
+        "status": status,
+        "statusMessage": status_message,
+        "attributes": attributes_to_json(&span.attributes),
+        "events": events,
+    })
+}
+
+fn attributes_to_json(attributes: &[KeyValue]) -> Map<String, Value> {
+    attributes
+        .iter()
+        .map(|attribute| {
+            (
+                attribute.key.to_string(),
+                Value::String(attribute.value.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Micros, true)
+}
@@ -0,0 +1,107 @@
+use anyhow::Context;
+use clap::ValueEnum;
+use opentelemetry::global;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    Resource,
+    propagation::TraceContextPropagator,
+    trace::{Sampler, SdkTracerProvider, SpanExporter},
+};
+
+use crate::{config::Config, services::telemetry::file::FileSpanExporter};
+
+pub mod file;
+
+/// Instrumentation scope reported on every span.
+pub const TRACER_NAME: &str = env!("CARGO_PKG_NAME");
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum TraceExporter {
+    None,
+    Otlp,
+    File,
+}
+
+/// Builds the tracer provider selected by `OTEL_TRACES_EXPORTER`, or `None`
+/// when tracing export is off. Also installs the W3C trace context
+/// propagator so incoming `traceparent` headers are honoured.
+pub fn init_tracer_provider(config: &Config) -> anyhow::Result<Option<SdkTracerProvider>> {
+    let provider = match config.otel_traces_exporter {
+        TraceExporter::None => return Ok(None),
+        TraceExporter::Otlp => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_endpoint(&config.otel_exporter_otlp_traces_endpoint)
+                .build()
+                .context("could not build the OTLP span exporter")?;
+            build_tracer_provider(
+                exporter,
+                &config.otel_service_name,
+                config.otel_traces_sampler_arg,
+            )
+        }
+        TraceExporter::File => {
+            let exporter = FileSpanExporter::new(&config.otel_traces_file_path)?;
+            build_tracer_provider(
+                exporter,
+                &config.otel_service_name,
+                config.otel_traces_sampler_arg,
+            )
+        }
+    };
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    Ok(Some(provider))
+}
+
+/// Batches spans into `exporter`. Root spans are sampled at `sampling_ratio`
+/// and child spans follow their parent, so a trace is kept or dropped whole.
+/// Tests can pass the SDK's `InMemorySpanExporter` here.
+pub fn build_tracer_provider<E: SpanExporter + 'static>(
+    exporter: E,
+    service_name: &str,
+    sampling_ratio: f64,
+) -> SdkTracerProvider {
+    SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            sampling_ratio,
+        ))))
+        .with_resource(
+            Resource::builder()
+                .with_service_name(service_name.to_string())
+                .build(),
+        )
+        .build()
+}
+
+/// Parses `OTEL_TRACES_SAMPLER_ARG`; used as a clap value parser.
+pub fn parse_sampling_ratio(value: &str) -> Result<f64, String> {
+    let ratio: f64 = value
+        .parse()
+        .map_err(|err| format!("invalid sampling ratio: {}", err))?;
+    if !(0.0..=1.0).contains(&ratio) {
+        return Err(format!("sampling ratio {} is not between 0 and 1", ratio));
+    }
+    Ok(ratio)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_ratios_between_zero_and_one() {
+        assert_eq!(parse_sampling_ratio("0"), Ok(0.0));
+        assert_eq!(parse_sampling_ratio("0.25"), Ok(0.25));
+        assert_eq!(parse_sampling_ratio("1.0"), Ok(1.0));
+    }
+
+    #[test]
+    fn rejects_out_of_range_and_malformed_ratios() {
+        assert!(parse_sampling_ratio("1.5").is_err());
+        assert!(parse_sampling_ratio("-0.1").is_err());
+        assert!(parse_sampling_ratio("NaN").is_err());
+        assert!(parse_sampling_ratio("half").is_err());
+    }
+}
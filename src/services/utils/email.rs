@@ -0,0 +1,52 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Brings an email address into the form it is stored and looked up in:
+/// surrounding whitespace trimmed, Unicode NFC, and a lowercase domain. The
+/// local part keeps its case; accounts are still matched case-insensitively
+/// on the whole address.
+///
+/// Provider-specific folding, such as dropping dots and `+tags` for Gmail, is
+/// not applied: it would change the address mail is sent to, and stored
+/// addresses written before such a rule would no longer match their logins.
+pub fn normalize_email(raw: &str) -> String {
+    let email: String = raw.trim().nfc().collect();
+
+    match email.rsplit_once('@') {
+        Some((local, domain)) => format!("{}@{}", local, domain.to_lowercase()),
+        None => email,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trims_and_lowercases_only_the_domain() {
+        assert_eq!(
+            normalize_email("  Jane.Doe@Example.COM \n"),
+            "Jane.Doe@example.com"
+        );
+    }
+
+    #[test]
+    fn composes_to_nfc() {
+        assert_eq!(
+            normalize_email("Jose\u{301}@example.com"),
+            "Jos\u{e9}@example.com"
+        );
+    }
+
+    #[test]
+    fn splits_on_the_last_at_sign() {
+        assert_eq!(
+            normalize_email("\"a@b\"@EXAMPLE.com"),
+            "\"a@b\"@example.com"
+        );
+    }
+
+    #[test]
+    fn leaves_addresses_without_domain_alone() {
+        assert_eq!(normalize_email(" NoDomain "), "NoDomain");
+    }
+}
@@ -1,8 +1,36 @@
+use chrono::{DateTime, Utc};
+
 use crate::dal::account::Account;
 
 #[derive(Debug)]
 pub enum AppError {
-    EmailRegistered { account: Account },
+    EmailRegistered { account: Box<Account> },
     SqlxError { msg: String },
     InvalidCredentials { msg: String },
+    InvalidSession { msg: String },
+    HashError { msg: String },
+    InvalidToken { msg: String },
+    RefreshTokenReused { msg: String },
+    MailError { msg: String },
+    EmailNotVerified { msg: String },
+    AccountLocked { until: DateTime<Utc> },
+    TooManyAttempts { msg: String },
+    CryptoError { msg: String },
+    TotpUnavailable { msg: String },
+    TotpState { msg: String },
+    InvalidTotpCode { msg: String },
+    PasswordReused { msg: String },
+    AccountDeletionState { msg: String },
+    DataExportState { msg: String },
+    FileError { msg: String },
+    AccountNotFound { msg: String },
+    AccountDisabled { msg: String },
+    AdminAction { msg: String },
+    InvalidApiKey { msg: String },
+    ApiKeyNotFound { msg: String },
+    InsufficientRole { msg: String },
+    OidcProviderNotFound { msg: String },
+    OidcProvider { msg: String },
+    InvalidOidcLogin { msg: String },
+    LoginLinkDisabled { msg: String },
 }
@@ -1 +1,6 @@
+pub mod crypto;
+pub mod email;
 pub mod error;
+pub mod password;
+pub mod token;
+pub mod totp;
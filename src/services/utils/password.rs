@@ -0,0 +1,180 @@
+use argon2::{
+    Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version,
+    password_hash::SaltString,
+};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::Config,
+    services::utils::{error::AppError, token::constant_time_eq},
+};
+
+const SALT_BYTES: usize = 16;
+
+pub enum PasswordCheck {
+    Valid { needs_rehash: bool },
+    Invalid,
+}
+
+/// Hashes a password with Argon2id using the cost parameters from `Config`.
+/// Argon2 is deliberately CPU-heavy, so the work runs on the blocking pool.
+pub async fn hash_password(config: &Config, password: &str) -> Result<String, AppError> {
+    let params = argon2_params(config)?;
+    let password = password.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let mut salt_bytes = [0u8; SALT_BYTES];
+        rand::rng().fill_bytes(&mut salt_bytes);
+        let salt = SaltString::encode_b64(&salt_bytes).map_err(|err| AppError::HashError {
+            msg: format!("Failed to encode salt: {}", err),
+        })?;
+
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|err| AppError::HashError {
+                msg: format!("Failed to hash password: {}", err),
+            })
+    })
+    .await
+    .map_err(|err| AppError::HashError {
+        msg: format!("Password hashing task failed: {}", err),
+    })?
+}
+
+/// Verifies a password against a stored hash. Legacy unsalted SHA-256 hashes
+/// and Argon2 hashes with outdated cost parameters are reported as needing a
+/// rehash so the caller can upgrade them after a successful login.
+pub async fn verify_password(
+    config: &Config,
+    password: &str,
+    stored_hash: &str,
+) -> Result<PasswordCheck, AppError> {
+    if !stored_hash.starts_with('$') {
+        let matches = constant_time_eq(legacy_sha256(password).as_bytes(), stored_hash.as_bytes());
+        return Ok(match matches {
+            true => PasswordCheck::Valid { needs_rehash: true },
+            false => PasswordCheck::Invalid,
+        });
+    }
+
+    let params = argon2_params(config)?;
+    let password = password.to_string();
+    let stored_hash = stored_hash.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let parsed = PasswordHash::new(&stored_hash).map_err(|err| AppError::HashError {
+            msg: format!("Stored password hash is malformed: {}", err),
+        })?;
+
+        if Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_err()
+        {
+            return Ok(PasswordCheck::Invalid);
+        }
+
+        let needs_rehash = parsed.algorithm != Algorithm::Argon2id.ident()
+            || Params::try_from(&parsed).map_or(true, |stored| {
+                stored.m_cost() != params.m_cost()
+                    || stored.t_cost() != params.t_cost()
+                    || stored.p_cost() != params.p_cost()
+            });
+
+        Ok(PasswordCheck::Valid { needs_rehash })
+    })
+    .await
+    .map_err(|err| AppError::HashError {
+        msg: format!("Password verification task failed: {}", err),
+    })?
+}
+
+fn argon2_params(config: &Config) -> Result<Params, AppError> {
+    Params::new(
+        config.argon2_memory_kib,
+        config.argon2_iterations,
+        config.argon2_parallelism,
+        None,
+    )
+    .map_err(|err| AppError::HashError {
+        msg: format!("Invalid Argon2 parameters: {}", err),
+    })
+}
+
+fn legacy_sha256(password: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(password);
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn test_config() -> Config {
+        let mut config = Config::try_parse_from(["test", "postgres://localhost/test", "1"])
+            .expect("positional arguments cover every required setting");
+        config.argon2_memory_kib = 1024;
+        config.argon2_iterations = 1;
+        config.argon2_parallelism = 1;
+        config
+    }
+
+    #[tokio::test]
+    async fn hashed_password_verifies_without_rehash() {
+        let config = test_config();
+        let hash = hash_password(&config, "correct horse").await.unwrap();
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(matches!(
+            verify_password(&config, "correct horse", &hash)
+                .await
+                .unwrap(),
+            PasswordCheck::Valid {
+                needs_rehash: false
+            }
+        ));
+        assert!(matches!(
+            verify_password(&config, "wrong horse", &hash)
+                .await
+                .unwrap(),
+            PasswordCheck::Invalid
+        ));
+    }
+
+    #[tokio::test]
+    async fn legacy_sha256_hash_verifies_and_needs_rehash() {
+        let config = test_config();
+        let legacy_hash = legacy_sha256("correct horse");
+
+        assert!(matches!(
+            verify_password(&config, "correct horse", &legacy_hash)
+                .await
+                .unwrap(),
+            PasswordCheck::Valid { needs_rehash: true }
+        ));
+        assert!(matches!(
+            verify_password(&config, "wrong horse", &legacy_hash)
+                .await
+                .unwrap(),
+            PasswordCheck::Invalid
+        ));
+    }
+
+    #[tokio::test]
+    async fn changed_cost_parameters_need_rehash() {
+        let mut config = test_config();
+        let hash = hash_password(&config, "correct horse").await.unwrap();
+        config.argon2_iterations = 2;
+
+        assert!(matches!(
+            verify_password(&config, "correct horse", &hash)
+                .await
+                .unwrap(),
+            PasswordCheck::Valid { needs_rehash: true }
+        ));
+    }
+}
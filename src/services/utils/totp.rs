@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+
+use crate::services::utils::token::constant_time_eq;
+
+const SECRET_BYTES: usize = 20;
+const DIGITS: u32 = 6;
+const PERIOD_SECONDS: i64 = 30;
+const ALLOWED_SKEW_STEPS: i64 = 1;
+
+/// Generates a 160-bit TOTP secret, the size RFC 4226 recommends for SHA-1.
+pub fn generate_secret() -> Vec<u8> {
+    let mut secret = vec![0u8; SECRET_BYTES];
+    rand::rng().fill_bytes(&mut secret);
+    secret
+}
+
+pub fn encode_secret(secret: &[u8]) -> String {
+    BASE32_NOPAD.encode(secret)
+}
+
+/// Builds the `otpauth://` URI authenticator apps read from a QR code.
+pub fn otpauth_uri(issuer: &str, account_name: &str, secret: &[u8]) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account_name),
+        encode_secret(secret),
+        percent_encode(issuer),
+        DIGITS,
+        PERIOD_SECONDS
+    )
+}
+
+/// Checks `code` against the current time step and one step either side.
+/// Returns the matched step, which must be newer than `last_used_step` so a
+/// code cannot be replayed within its validity window.
+pub fn verify_code(
+    secret: &[u8],
+    code: &str,
+    now: DateTime<Utc>,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    let current_step = now.timestamp() / PERIOD_SECONDS;
+
+    (current_step - ALLOWED_SKEW_STEPS..=current_step + ALLOWED_SKEW_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| constant_time_eq(hotp(secret, *step as u64).as_bytes(), code.as_bytes()))
+}
+
+fn hotp(secret: &[u8], counter: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA-1, truncated to six digits.
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    fn at(timestamp: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(timestamp, 0).unwrap()
+    }
+
+    #[test]
+    fn matches_rfc_6238_vectors() {
+        assert_eq!(hotp(RFC_SECRET, 59 / 30), "287082");
+        assert_eq!(hotp(RFC_SECRET, 1111111109 / 30), "081804");
+        assert_eq!(hotp(RFC_SECRET, 2000000000 / 30), "279037");
+    }
+
+    #[test]
+    fn accepts_one_step_of_skew_either_side() {
+        let step = 1111111109 / 30;
+
+        assert_eq!(
+            verify_code(RFC_SECRET, "081804", at(1111111109), None),
+            Some(step)
+        );
+        assert_eq!(
+            verify_code(RFC_SECRET, "081804", at(1111111109 + 30), None),
+            Some(step)
+        );
+        assert_eq!(
+            verify_code(RFC_SECRET, "081804", at(1111111109 - 30), None),
+            Some(step)
+        );
+        assert_eq!(
+            verify_code(RFC_SECRET, "081804", at(1111111109 + 90), None),
+            None
+        );
+    }
+
+    #[test]
+    fn rejects_replayed_and_wrong_codes() {
+        let step = 1111111109 / 30;
+
+        assert_eq!(
+            verify_code(RFC_SECRET, "081804", at(1111111109), Some(step)),
+            None
+        );
+        assert_eq!(
+            verify_code(RFC_SECRET, "081805", at(1111111109), None),
+            None
+        );
+    }
+
+    #[test]
+    fn otpauth_uri_escapes_labels() {
+        assert_eq!(
+            otpauth_uri("Plug and Plant", "a@b.io", RFC_SECRET),
+            "otpauth://totp/Plug%20and%20Plant:a%40b.io?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Plug%20and%20Plant&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Runs the OIDC client against the mock issuer from the examples.
+
+#[path = "../examples/mock_oidc_issuer.rs"]
+mod mock_oidc_issuer;
+
+use clap::Parser;
+use plug_and_plant_be_axum_sqlx::{
+    config::Config,
+    services::{
+        oidc::{OidcClient, OidcProviderConfig, parse_oidc_providers, pkce_challenge},
+        utils::error::AppError,
+    },
+};
+
+const REDIRECT_URI: &str = "http://127.0.0.1:3000/account/oidc/mock/callback";
+const CODE_VERIFIER: &str = "a-code-verifier-long-enough-for-pkce-0123456789";
+const NONCE: &str = "test-nonce";
+
+/// Serves a mock issuer on a free port and returns a provider pointing at it.
+async fn start_issuer(client_id: &str) -> OidcProviderConfig {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let issuer =
+        mock_oidc_issuer::Issuer::new(url.clone(), String::from("oidc.user@example.com"), true);
+    tokio::spawn(async move {
+        axum::serve(listener, mock_oidc_issuer::router(issuer))
+            .await
+            .unwrap();
+    });
+
+    provider(&url, client_id)
+}
+
+fn provider(issuer: &str, client_id: &str) -> OidcProviderConfig {
+    let providers = parse_oidc_providers(&format!(
+        r#"[{{"name":"mock","issuer":"{}","clientId":"{}"}}]"#,
+        issuer, client_id
+    ))
+    .unwrap();
+    providers.find("mock").unwrap().clone()
+}
+
+fn client() -> OidcClient {
+    let config = Config::try_parse_from(["test", "postgres://localhost/test", "1"]).unwrap();
+    OidcClient::new(&config).unwrap()
+}
+
+/// Walks the authorization endpoint the way a browser would and returns the
+/// code it redirects back with.
+async fn authorize(oidc: &OidcClient, provider: &OidcProviderConfig, login_hint: &str) -> String {
+    let metadata = oidc.metadata(provider).await.unwrap();
+    let mut url = url::Url::parse(&metadata.authorization_endpoint).unwrap();
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", REDIRECT_URI)
+        .append_pair("state", "test-state")
+        .append_pair("nonce", NONCE)
+        .append_pair("code_challenge", &pkce_challenge(CODE_VERIFIER))
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("login_hint", login_hint);
+
+    let response = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .unwrap()
+        .get(url)
+        .send()
+        .await
+        .unwrap();
+    let location = response.headers()["location"].to_str().unwrap();
+    let location = url::Url::parse(location).unwrap();
+    let query = |name: &str| {
+        location
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    assert_eq!(query("state").as_deref(), Some("test-state"));
+    query("code").unwrap()
+}
+
+#[tokio::test]
+async fn discovers_the_issuer_endpoints() {
+    let provider = start_issuer("plug-and-plant").await;
+    let metadata = client().metadata(&provider).await.unwrap();
+
+    assert_eq!(metadata.issuer, provider.issuer);
+    assert_eq!(
+        metadata.authorization_endpoint,
+        format!("{}/authorize", provider.issuer)
+    );
+    assert_eq!(
+        metadata.token_endpoint,
+        format!("{}/token", provider.issuer)
+    );
+    assert_eq!(metadata.jwks_uri, format!("{}/jwks", provider.issuer));
+}
+
+#[tokio::test]
+async fn verifies_an_id_token_against_the_published_keys() {
+    let provider = start_issuer("plug-and-plant").await;
+    let oidc = client();
+
+    let code = authorize(&oidc, &provider, "ada@example.com").await;
+    let id_token = oidc
+        .exchange_code(&provider, REDIRECT_URI, &code, CODE_VERIFIER)
+        .await
+        .unwrap();
+    let claims = oidc
+        .verify_id_token(&provider, &id_token, NONCE)
+        .await
+        .unwrap();
+
+    assert_eq!(claims.sub, "mock|ada@example.com");
+    assert_eq!(claims.email.as_deref(), Some("ada@example.com"));
+    assert!(claims.email_verified);
+}
+
+#[tokio::test]
+async fn rejects_an_id_token_with_another_nonce() {
+    let provider = start_issuer("plug-and-plant").await;
+    let oidc = client();
+
+    let code = authorize(&oidc, &provider, "ada@example.com").await;
+    let id_token = oidc
+        .exchange_code(&provider, REDIRECT_URI, &code, CODE_VERIFIER)
+        .await
+        .unwrap();
+    let result = oidc
+        .verify_id_token(&provider, &id_token, "another-nonce")
+        .await;
+
+    assert!(matches!(result, Err(AppError::InvalidOidcLogin { .. })));
+}
+
+#[tokio::test]
+async fn rejects_an_id_token_issued_to_another_client() {
+    let provider = start_issuer("plug-and-plant").await;
+    let oidc = client();
+
+    let code = authorize(&oidc, &provider, "ada@example.com").await;
+    let id_token = oidc
+        .exchange_code(&provider, REDIRECT_URI, &code, CODE_VERIFIER)
+        .await
+        .unwrap();
+    let other_client = self::provider(&provider.issuer, "another-client");
+    let result = oidc.verify_id_token(&other_client, &id_token, NONCE).await;
+
+    assert!(matches!(result, Err(AppError::InvalidOidcLogin { .. })));
+}
+
+#[tokio::test]
+async fn refuses_a_code_redeemed_without_its_verifier() {
+    let provider = start_issuer("plug-and-plant").await;
+    let oidc = client();
+
+    let code = authorize(&oidc, &provider, "ada@example.com").await;
+    let result = oidc
+        .exchange_code(&provider, REDIRECT_URI, &code, "another-code-verifier")
+        .await;
+
+    assert!(result.is_err());
+}